@@ -0,0 +1,2 @@
+// re-exports the wasm-bindgen bindings of hex, build with `wasm-pack build hex-wasm --target web`
+pub use hex::wasm::*;
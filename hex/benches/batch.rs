@@ -0,0 +1,110 @@
+// batch conversions against calling the generic conversions per element
+// cargo bench --bench batch
+// criterion doesn't build for wasm32, so the benchmarks are empty there
+
+#[cfg(not(target_arch = "wasm32"))]
+mod bench {
+    use criterion::{criterion_group, Criterion};
+    use hex::{flat, pointy, Cube, Offset};
+    use std::hint::black_box;
+
+    const COUNT: usize = 10_000;
+    const TILE_SIZE: (f32, f32) = (42.0, 30.0);
+
+    fn get_points() -> Vec<(f32, f32)> {
+        (0..COUNT)
+            .map(|i| {
+                (
+                    (i % 100) as f32 * 13.7 - 600.0,
+                    (i / 100) as f32 * 11.3 - 500.0,
+                )
+            })
+            .collect()
+    }
+
+    fn points_to_offsets(c: &mut Criterion) {
+        let points = get_points();
+        let mut offsets = vec![Offset { q: 0, r: 0 }; COUNT];
+        let mut group = c.benchmark_group("points_to_offsets");
+        group.bench_function("flat per element", |b| {
+            b.iter(|| {
+                for (point, offset) in points.iter().zip(offsets.iter_mut()) {
+                    *offset =
+                        flat::coordinate::convert_point_to_offset(TILE_SIZE, *black_box(point));
+                }
+            })
+        });
+        group.bench_function("flat batch", |b| {
+            b.iter(|| {
+                flat::batch::convert_points_to_offsets(TILE_SIZE, black_box(&points), &mut offsets)
+            })
+        });
+        group.bench_function("pointy per element", |b| {
+            b.iter(|| {
+                for (point, offset) in points.iter().zip(offsets.iter_mut()) {
+                    *offset =
+                        pointy::coordinate::convert_point_to_offset(TILE_SIZE, *black_box(point));
+                }
+            })
+        });
+        group.bench_function("pointy batch", |b| {
+            b.iter(|| {
+                pointy::batch::convert_points_to_offsets(
+                    TILE_SIZE,
+                    black_box(&points),
+                    &mut offsets,
+                )
+            })
+        });
+        group.finish();
+    }
+
+    fn offsets_to_points(c: &mut Criterion) {
+        let mut offsets = vec![Offset { q: 0, r: 0 }; COUNT];
+        flat::batch::convert_points_to_offsets(TILE_SIZE, &get_points(), &mut offsets);
+        let mut points = vec![(0.0, 0.0); COUNT];
+        let mut group = c.benchmark_group("offsets_to_points");
+        group.bench_function("flat per element", |b| {
+            b.iter(|| {
+                for (offset, point) in offsets.iter().zip(points.iter_mut()) {
+                    *point =
+                        flat::coordinate::convert_offset_to_point(TILE_SIZE, *black_box(offset));
+                }
+            })
+        });
+        group.bench_function("flat batch", |b| {
+            b.iter(|| {
+                flat::batch::convert_offsets_to_points(TILE_SIZE, black_box(&offsets), &mut points)
+            })
+        });
+        group.finish();
+    }
+
+    fn distances(c: &mut Criterion) {
+        let src = Cube { q: 3, r: -7, s: 4 };
+        let cubes: Vec<Cube<i32>> = flat::neighbor::get_cubes_within_range(src, 57)
+            .take(COUNT)
+            .collect();
+        let mut distances = vec![0; cubes.len()];
+        let mut group = c.benchmark_group("distances");
+        group.bench_function("per element", |b| {
+            b.iter(|| {
+                for (cube, distance) in cubes.iter().zip(distances.iter_mut()) {
+                    *distance = flat::neighbor::calculate_distance(src, *black_box(cube));
+                }
+            })
+        });
+        group.bench_function("batch", |b| {
+            b.iter(|| flat::batch::calculate_distances(src, black_box(&cubes), &mut distances))
+        });
+        group.finish();
+    }
+
+    criterion_group!(benches, points_to_offsets, offsets_to_points, distances);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(bench::benches);
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
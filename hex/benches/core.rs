@@ -0,0 +1,201 @@
+// the core algorithms across radii and integer types
+// cargo bench --bench core
+// empty on wasm32 like benches/batch.rs, since criterion doesn't build there
+
+#[cfg(not(target_arch = "wasm32"))]
+mod bench {
+    use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+    use hex::flat::neighbor::Number;
+    use hex::{flat, pointy, Cube, Offset};
+    use num::cast::AsPrimitive;
+    use std::hint::black_box;
+
+    const RADII: [usize; 4] = [1, 4, 16, 64];
+    const TILE_SIZE: (f32, f32) = (42.0, 30.0);
+
+    fn within_range<T>(c: &mut Criterion, name: &str)
+    where
+        T: Number + 'static,
+        usize: AsPrimitive<T>,
+        std::ops::Range<T>: Iterator<Item = T>,
+    {
+        let mut group = c.benchmark_group(format!("get_cubes_within_range/{}", name));
+        let (q, r): (T, T) = (3.as_(), 4.as_());
+        let src = Cube { q, r, s: -q - r };
+        for radius in RADII {
+            group.throughput(Throughput::Elements((3 * radius * (radius + 1) + 1) as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(radius), &radius, |b, radius| {
+                b.iter(|| {
+                    flat::neighbor::get_cubes_within_range(black_box(src), radius.as_()).for_each(
+                        |cube| {
+                            black_box(cube);
+                        },
+                    )
+                })
+            });
+        }
+        group.finish();
+    }
+
+    fn nth_nearest<T>(c: &mut Criterion, name: &str)
+    where
+        T: Number + 'static,
+        usize: AsPrimitive<T>,
+        std::ops::Range<T>: Iterator<Item = T>,
+    {
+        let mut group = c.benchmark_group(format!("get_nth_nearest_cubes/{}", name));
+        let src = Cube {
+            q: num::zero(),
+            r: num::zero(),
+            s: num::zero(),
+        };
+        for radius in RADII {
+            group.throughput(Throughput::Elements((6 * radius) as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(radius), &radius, |b, radius| {
+                b.iter(|| {
+                    flat::neighbor::get_nth_nearest_cubes(black_box(src), radius.as_()).for_each(
+                        |cube| {
+                            black_box(cube);
+                        },
+                    )
+                })
+            });
+        }
+        group.finish();
+    }
+
+    fn distance<T>(c: &mut Criterion, name: &str)
+    where
+        T: Number + 'static,
+        usize: AsPrimitive<T>,
+        std::ops::Range<T>: Iterator<Item = T>,
+    {
+        let src = Cube {
+            q: num::zero(),
+            r: num::zero(),
+            s: num::zero(),
+        };
+        let cubes: Vec<Cube<T>> = flat::neighbor::get_cubes_within_range(src, 16.as_()).collect();
+        c.bench_function(&format!("calculate_distance/{}", name), |b| {
+            b.iter(|| {
+                cubes
+                    .iter()
+                    .map(|cube| flat::neighbor::calculate_distance(black_box(src), *cube))
+                    .fold(num::zero::<T>(), |sum, distance| sum + distance)
+            })
+        });
+    }
+
+    fn core_integers(c: &mut Criterion) {
+        within_range::<i16>(c, "i16");
+        within_range::<i32>(c, "i32");
+        within_range::<i64>(c, "i64");
+        nth_nearest::<i16>(c, "i16");
+        nth_nearest::<i32>(c, "i32");
+        nth_nearest::<i64>(c, "i64");
+        distance::<i16>(c, "i16");
+        distance::<i32>(c, "i32");
+        distance::<i64>(c, "i64");
+    }
+
+    fn get_points() -> Vec<(f32, f32)> {
+        (0..1000)
+            .map(|i| {
+                (
+                    (i % 40) as f32 * 13.7 - 250.0,
+                    (i / 40) as f32 * 11.3 - 120.0,
+                )
+            })
+            .collect()
+    }
+
+    fn round(c: &mut Criterion) {
+        let cubes: Vec<Cube<f32>> = get_points()
+            .into_iter()
+            .map(|(q, r)| Cube {
+                q: q / 7.0,
+                r: r / 5.0,
+                s: -q / 7.0 - r / 5.0,
+            })
+            .collect();
+        let mut group = c.benchmark_group("round_cube");
+        group.bench_function("f32 to i32", |b| {
+            b.iter(|| {
+                for cube in &cubes {
+                    black_box(flat::round::round_cube::<i32, f32>(*cube));
+                }
+            })
+        });
+        let cubes: Vec<Cube<f64>> = cubes
+            .iter()
+            .map(|cube| Cube {
+                q: cube.q as f64,
+                r: cube.r as f64,
+                s: cube.s as f64,
+            })
+            .collect();
+        group.bench_function("f64 to i64", |b| {
+            b.iter(|| {
+                for cube in &cubes {
+                    black_box(flat::round::round_cube::<i64, f64>(*cube));
+                }
+            })
+        });
+        group.finish();
+    }
+
+    fn pixel(c: &mut Criterion) {
+        let points = get_points();
+        let offsets: Vec<Offset<i32>> = points
+            .iter()
+            .map(|point| flat::coordinate::convert_point_to_offset(TILE_SIZE, *point))
+            .collect();
+        let mut group = c.benchmark_group("pixel");
+        group.throughput(Throughput::Elements(points.len() as u64));
+        group.bench_function("flat point to offset", |b| {
+            b.iter(|| {
+                for point in &points {
+                    black_box(flat::coordinate::convert_point_to_offset::<i32, f32>(
+                        TILE_SIZE, *point,
+                    ));
+                }
+            })
+        });
+        group.bench_function("flat offset to point", |b| {
+            b.iter(|| {
+                for offset in &offsets {
+                    black_box(flat::coordinate::convert_offset_to_point(
+                        TILE_SIZE, *offset,
+                    ));
+                }
+            })
+        });
+        group.bench_function("pointy point to offset", |b| {
+            b.iter(|| {
+                for point in &points {
+                    black_box(pointy::coordinate::convert_point_to_offset::<i32, f32>(
+                        TILE_SIZE, *point,
+                    ));
+                }
+            })
+        });
+        group.bench_function("pointy offset to point", |b| {
+            b.iter(|| {
+                for offset in &offsets {
+                    black_box(pointy::coordinate::convert_offset_to_point(
+                        TILE_SIZE, *offset,
+                    ));
+                }
+            })
+        });
+        group.finish();
+    }
+
+    criterion_group!(benches, core_integers, round, pixel);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(bench::benches);
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
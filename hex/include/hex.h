@@ -0,0 +1,152 @@
+#ifndef HEX_H
+#define HEX_H
+
+/* generated by cbindgen from src/ffi.rs, do not edit */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef enum HexOrientation {
+  HEX_FLAT = 0,
+  HEX_POINTY = 1,
+} HexOrientation;
+
+typedef struct HexMap HexMap;
+
+typedef struct HexOffset {
+  int32_t q;
+  int32_t r;
+} HexOffset;
+
+typedef struct HexCube {
+  int32_t q;
+  int32_t r;
+  int32_t s;
+} HexCube;
+
+typedef struct HexAxial {
+  int32_t q;
+  int32_t r;
+} HexAxial;
+
+typedef struct HexPoint {
+  double x;
+  double y;
+} HexPoint;
+
+// Writes the cube of offset into out.
+// Returns false, leaving out untouched, when orientation is not a HexOrientation or the cube doesn't fit in int32_t.
+//
+// # Safety
+// out must be null or point to a writable HexCube.
+bool hex_convert_offset_to_cube(uint32_t orientation,
+                                struct HexOffset offset,
+                                struct HexCube *out);
+
+// Writes the offset of cube into out.
+// Returns false, leaving out untouched, when orientation is not a HexOrientation, q + r + s != 0
+// or the offset doesn't fit in int32_t.
+//
+// # Safety
+// out must be null or point to a writable HexOffset.
+bool hex_convert_cube_to_offset(uint32_t orientation, struct HexCube cube, struct HexOffset *out);
+
+// Writes the axial coordinates of cube into out. Returns false, leaving out untouched, when q + r + s != 0.
+//
+// # Safety
+// out must be null or point to a writable HexAxial.
+bool hex_convert_cube_to_axial(struct HexCube cube,
+                               struct HexAxial *out);
+
+// Writes the cube of axial into out. Returns false, leaving out untouched, when s doesn't fit in int32_t.
+//
+// # Safety
+// out must be null or point to a writable HexCube.
+bool hex_convert_axial_to_cube(struct HexAxial axial,
+                               struct HexCube *out);
+
+// Writes the center of offset into out, size is the width and height of a tile.
+// Returns false, leaving out untouched, when orientation is not a HexOrientation
+// or the offset is too far out to have a cube in int32_t.
+//
+// # Safety
+// out must be null or point to a writable HexPoint.
+bool hex_convert_offset_to_point(uint32_t orientation,
+                                 struct HexPoint size,
+                                 struct HexOffset offset,
+                                 struct HexPoint *out);
+
+// Writes the tile under point into out, for picking.
+// Returns false, leaving out untouched, when orientation is not a HexOrientation or the tile doesn't fit
+// in int32_t, which includes points that aren't finite and a size of 0.
+//
+// # Safety
+// out must be null or point to a writable HexOffset.
+bool hex_convert_point_to_offset(uint32_t orientation,
+                                 struct HexPoint size,
+                                 struct HexPoint point,
+                                 struct HexOffset *out);
+
+// Writes the distance between src and dst into out.
+// Returns false, leaving out untouched, when a cube is invalid or the distance doesn't fit in int32_t.
+//
+// # Safety
+// out must be null or point to a writable int32_t.
+bool hex_calculate_distance(struct HexCube src,
+                            struct HexCube dst,
+                            int32_t *out);
+
+// Writes the 6 neighbors of cube, in direction order, into out.
+// Returns false, leaving out untouched, when cube is invalid or a neighbor doesn't fit in int32_t.
+//
+// # Safety
+// out must be null or point to at least 6 writable HexCube.
+bool hex_get_neighbors(struct HexCube cube, struct HexCube *out);
+
+// Writes the cubes within n steps of cube into out, up to capacity, and returns how many there are.
+// Returns 0 when cube is invalid, n is negative or a cube in range doesn't fit in int32_t.
+//
+// # Safety
+// out must be null or point to at least capacity writable HexCube.
+size_t hex_get_cubes_within_range(struct HexCube cube,
+                                  int32_t n,
+                                  struct HexCube *out,
+                                  size_t capacity);
+
+// A width x height map where every tile costs 1 to enter.
+// Returns null when orientation is not a HexOrientation, width or height is above INT32_MAX,
+// or the tiles can't be allocated.
+struct HexMap *hex_map_new(uint32_t orientation, size_t width, size_t height);
+
+// # Safety
+// map must be null or come from hex_map_new and not be used afterwards.
+void hex_map_free(struct HexMap *map);
+
+// Sets the cost to enter a tile, 0 marks an obstacle. Returns false outside of the map.
+//
+// # Safety
+// map must come from hex_map_new.
+bool hex_map_set_cost(struct HexMap *map, struct HexOffset offset, uint32_t cost);
+
+// The cost to enter a tile, 0 for obstacles and tiles outside of the map.
+//
+// # Safety
+// map must come from hex_map_new.
+uint32_t hex_map_get_cost(const struct HexMap *map, struct HexOffset offset);
+
+// Finds the cheapest path from start to goal, both included, with A*.
+// Writes the path into out up to capacity and its total cost into total_cost when not null.
+// Returns the path length, or 0 when there is no path.
+//
+// # Safety
+// map must come from hex_map_new, out must be null or point to at least capacity writable HexOffset,
+// total_cost must be null or point to a writable uint32_t.
+size_t hex_map_find_path(const struct HexMap *map,
+                         struct HexOffset start,
+                         struct HexOffset goal,
+                         struct HexOffset *out,
+                         size_t capacity,
+                         uint32_t *total_cost);
+
+#endif  /* HEX_H */
@@ -0,0 +1,152 @@
+// bevy world space has y pointing up, which matches the y inversed points of flat and pointy
+// so points are used as translations directly
+
+use crate::structs::*;
+use ::bevy::prelude::*;
+use ::bevy::window::PrimaryWindow;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CubePosition(pub Cube<i32>);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OffsetPosition(pub Offset<i32>);
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct HexLayout {
+    pub orientation: Orientation,
+    pub size: (f32, f32),
+    pub origin: Vec2,
+}
+
+impl HexLayout {
+    pub fn convert_offset_to_point(&self, offset: Offset<i32>) -> Vec2 {
+        let point = self.orientation.convert_offset_to_point(self.size, offset);
+        return self.origin + Vec2::from(point);
+    }
+
+    pub fn convert_point_to_offset(&self, point: Vec2) -> Offset<i32> {
+        let point = (point - self.origin).into();
+        return self.orientation.convert_point_to_offset(self.size, point);
+    }
+
+    pub fn convert_cube_to_point(&self, cube: Cube<i32>) -> Vec2 {
+        let point = self.orientation.convert_cube_to_point(self.size, cube);
+        return self.origin + Vec2::from(point);
+    }
+}
+
+// the offset under the cursor of the primary window, None if the cursor is outside
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HoveredHex(pub Option<Offset<i32>>);
+
+pub struct HexMapPlugin {
+    pub layout: HexLayout,
+}
+
+impl Plugin for HexMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.layout)
+            .init_resource::<HoveredHex>()
+            .add_systems(
+                PostUpdate,
+                (sync_cube_transforms, sync_offset_transforms)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(PreUpdate, pick_hovered_hex);
+    }
+}
+
+// keeps translation z, so sprites can still be layered
+pub fn sync_cube_transforms(
+    layout: Res<HexLayout>,
+    mut query: Query<(Ref<CubePosition>, &mut Transform)>,
+) {
+    for (position, mut transform) in &mut query {
+        if !layout.is_changed() && !position.is_changed() {
+            continue;
+        }
+        let point = layout.convert_cube_to_point(position.0);
+        transform.translation = point.extend(transform.translation.z);
+    }
+}
+
+pub fn sync_offset_transforms(
+    layout: Res<HexLayout>,
+    mut query: Query<(Ref<OffsetPosition>, &mut Transform)>,
+) {
+    for (position, mut transform) in &mut query {
+        if !layout.is_changed() && !position.is_changed() {
+            continue;
+        }
+        let point = layout.convert_offset_to_point(position.0);
+        transform.translation = point.extend(transform.translation.z);
+    }
+}
+
+pub fn pick_hovered_hex(
+    layout: Res<HexLayout>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut hovered: ResMut<HoveredHex>,
+) {
+    let point = windows.get_single().ok().and_then(|window| {
+        let cursor = window.cursor_position()?;
+        let (camera, transform) = cameras.iter().find(|(camera, _)| camera.is_active)?;
+        return camera.viewport_to_world_2d(transform, cursor);
+    });
+    let offset = point.map(|point| layout.convert_point_to_offset(point));
+    if hovered.0 != offset {
+        hovered.0 = offset;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LAYOUT: HexLayout = HexLayout {
+        orientation: Orientation::Flat,
+        size: (42.0, 30.0),
+        origin: Vec2::new(100.0, 0.0),
+    };
+
+    #[test]
+    fn layout() {
+        let offset = Offset { q: 1, r: 2 };
+        let point = LAYOUT.convert_offset_to_point(offset);
+        assert_eq!(point, Vec2::new(142.0, 75.0));
+        assert_eq!(LAYOUT.convert_point_to_offset(point), offset);
+
+        let cube = LAYOUT.orientation.convert_offset_to_cube(offset);
+        assert_eq!(LAYOUT.convert_cube_to_point(cube), point);
+    }
+
+    #[test]
+    fn sync() {
+        let mut app = App::new();
+        app.insert_resource(LAYOUT)
+            .add_systems(Update, (sync_cube_transforms, sync_offset_transforms));
+        let offset = app
+            .world_mut()
+            .spawn((
+                OffsetPosition(Offset { q: 1, r: 2 }),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+            ))
+            .id();
+        let cube = app
+            .world_mut()
+            .spawn((CubePosition(Cube::new(1, 0)), Transform::default()))
+            .id();
+        app.update();
+
+        let transform = app.world().get::<Transform>(offset).unwrap();
+        assert_eq!(transform.translation, Vec3::new(142.0, 75.0, 5.0));
+        let transform = app.world().get::<Transform>(cube).unwrap();
+        assert_eq!(transform.translation, Vec3::new(142.0, 15.0, 0.0));
+
+        app.world_mut().get_mut::<OffsetPosition>(offset).unwrap().0 = Offset { q: 0, r: 0 };
+        app.update();
+        let transform = app.world().get::<Transform>(offset).unwrap();
+        assert_eq!(transform.translation, Vec3::new(100.0, 0.0, 5.0));
+    }
+}
@@ -0,0 +1,49 @@
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError;
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "coordinate arithmetic overflowed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OverflowError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidCube;
+
+impl fmt::Display for InvalidCube {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "q + r + s != 0")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCube {}
+
+// which part of a coordinate string could not be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseCoordinateError {
+    // missing brackets, separators or components
+    Syntax,
+    // the named component, 'q', 'r' or 's', is not a number
+    Component(char),
+    // only from strict parsing
+    InvalidCube,
+}
+
+impl fmt::Display for ParseCoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCoordinateError::Syntax => write!(f, "invalid coordinate syntax"),
+            ParseCoordinateError::Component(name) => write!(f, "invalid {} component", name),
+            ParseCoordinateError::InvalidCube => write!(f, "q + r + s != 0"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseCoordinateError {}
@@ -0,0 +1,680 @@
+// extern "C" bindings, the header is generated into include/hex.h with `cbindgen --output include/hex.h`
+// build a library with `cargo rustc --release --features ffi --crate-type staticlib` (or cdylib)
+// coordinates are int32_t and points are double, the map handle is opaque and must be freed with hex_map_free
+
+use crate::{shared::coordinate, shared::index, shared::neighbor, structs::*};
+use core::slice;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexOrientation {
+    HexFlat = 0,
+    HexPointy = 1,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexCube {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexAxial {
+    pub q: i32,
+    pub r: i32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexOffset {
+    pub q: i32,
+    pub r: i32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HexPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+// a width x height offset map of costs for pathfinding
+pub struct HexMap {
+    orientation: Orientation,
+    size: (usize, usize),
+    costs: Vec<Option<u32>>,
+}
+
+// orientations come in as integers, since a C enum outside of HexOrientation would be undefined behavior in rust
+fn check_orientation(orientation: u32) -> Option<Orientation> {
+    return match orientation {
+        o if o == HexOrientation::HexFlat as u32 => Some(Orientation::Flat),
+        o if o == HexOrientation::HexPointy as u32 => Some(Orientation::Pointy),
+        _ => None,
+    };
+}
+
+impl From<HexCube> for Cube<i32> {
+    fn from(cube: HexCube) -> Self {
+        return Cube {
+            q: cube.q,
+            r: cube.r,
+            s: cube.s,
+        };
+    }
+}
+
+impl From<Cube<i32>> for HexCube {
+    fn from(cube: Cube<i32>) -> Self {
+        return HexCube {
+            q: cube.q,
+            r: cube.r,
+            s: cube.s,
+        };
+    }
+}
+
+impl From<HexOffset> for Offset<i32> {
+    fn from(offset: HexOffset) -> Self {
+        return Offset {
+            q: offset.q,
+            r: offset.r,
+        };
+    }
+}
+
+impl From<Offset<i32>> for HexOffset {
+    fn from(offset: Offset<i32>) -> Self {
+        return HexOffset {
+            q: offset.q,
+            r: offset.r,
+        };
+    }
+}
+
+// every cube from C is checked, q + r + s is summed in i64 so the check itself can't overflow
+fn check_cube(cube: HexCube) -> Option<Cube<i32>> {
+    Cube::try_new(cube.q as i64, cube.r as i64, cube.s as i64).ok()?;
+    return Some(Cube::from(cube));
+}
+
+// writes the item when there is one and out isn't null, returns whether there was one
+unsafe fn write_item<T>(item: Option<T>, out: *mut T) -> bool {
+    let Some(item) = item else {
+        return false;
+    };
+    if !out.is_null() {
+        *out = item;
+    }
+    return true;
+}
+
+// copies as many items as fit and returns the total count, so callers can retry with a larger buffer
+unsafe fn write_items<T>(items: &[T], out: *mut T, capacity: usize) -> usize
+where
+    T: Copy,
+{
+    if !out.is_null() {
+        let count = items.len().min(capacity);
+        slice::from_raw_parts_mut(out, count).copy_from_slice(&items[..count]);
+    }
+    return items.len();
+}
+
+/// Writes the cube of offset into out.
+/// Returns false, leaving out untouched, when orientation is not a HexOrientation or the cube doesn't fit in int32_t.
+///
+/// # Safety
+/// out must be null or point to a writable HexCube.
+#[no_mangle]
+pub unsafe extern "C" fn hex_convert_offset_to_cube(
+    orientation: u32,
+    offset: HexOffset,
+    out: *mut HexCube,
+) -> bool {
+    let cube = check_orientation(orientation).and_then(|orientation| {
+        orientation
+            .try_convert_offset_to_cube(Offset::from(offset))
+            .ok()
+    });
+    return write_item(cube.map(HexCube::from), out);
+}
+
+/// Writes the offset of cube into out.
+/// Returns false, leaving out untouched, when orientation is not a HexOrientation, q + r + s != 0
+/// or the offset doesn't fit in int32_t.
+///
+/// # Safety
+/// out must be null or point to a writable HexOffset.
+#[no_mangle]
+pub unsafe extern "C" fn hex_convert_cube_to_offset(
+    orientation: u32,
+    cube: HexCube,
+    out: *mut HexOffset,
+) -> bool {
+    let Some(orientation) = check_orientation(orientation) else {
+        return false;
+    };
+    let offset =
+        check_cube(cube).and_then(|cube| orientation.try_convert_cube_to_offset(cube).ok());
+    return write_item(offset.map(HexOffset::from), out);
+}
+
+/// Writes the axial coordinates of cube into out. Returns false, leaving out untouched, when q + r + s != 0.
+///
+/// # Safety
+/// out must be null or point to a writable HexAxial.
+#[no_mangle]
+pub unsafe extern "C" fn hex_convert_cube_to_axial(cube: HexCube, out: *mut HexAxial) -> bool {
+    let axial = check_cube(cube).map(|cube| {
+        let axial = coordinate::convert_cube_to_axial(cube);
+        return HexAxial {
+            q: axial.q,
+            r: axial.r,
+        };
+    });
+    return write_item(axial, out);
+}
+
+/// Writes the cube of axial into out. Returns false, leaving out untouched, when s doesn't fit in int32_t.
+///
+/// # Safety
+/// out must be null or point to a writable HexCube.
+#[no_mangle]
+pub unsafe extern "C" fn hex_convert_axial_to_cube(axial: HexAxial, out: *mut HexCube) -> bool {
+    let axial = Axial {
+        q: axial.q,
+        r: axial.r,
+    };
+    let cube = coordinate::try_convert_axial_to_cube(axial);
+    return write_item(cube.ok().map(HexCube::from), out);
+}
+
+/// Writes the center of offset into out, size is the width and height of a tile.
+/// Returns false, leaving out untouched, when orientation is not a HexOrientation
+/// or the offset is too far out to have a cube in int32_t.
+///
+/// # Safety
+/// out must be null or point to a writable HexPoint.
+#[no_mangle]
+pub unsafe extern "C" fn hex_convert_offset_to_point(
+    orientation: u32,
+    size: HexPoint,
+    offset: HexOffset,
+    out: *mut HexPoint,
+) -> bool {
+    let Some(orientation) = check_orientation(orientation) else {
+        return false;
+    };
+    let point = orientation
+        .try_convert_offset_to_point((size.x, size.y), Offset::from(offset))
+        .ok()
+        .map(|(x, y)| HexPoint { x, y });
+    return write_item(point, out);
+}
+
+/// Writes the tile under point into out, for picking.
+/// Returns false, leaving out untouched, when orientation is not a HexOrientation or the tile doesn't fit
+/// in int32_t, which includes points that aren't finite and a size of 0.
+///
+/// # Safety
+/// out must be null or point to a writable HexOffset.
+#[no_mangle]
+pub unsafe extern "C" fn hex_convert_point_to_offset(
+    orientation: u32,
+    size: HexPoint,
+    point: HexPoint,
+    out: *mut HexOffset,
+) -> bool {
+    let Some(orientation) = check_orientation(orientation) else {
+        return false;
+    };
+    let offset = orientation
+        .try_convert_point_to_offset::<i32, f64>((size.x, size.y), (point.x, point.y))
+        .ok()
+        .map(HexOffset::from);
+    return write_item(offset, out);
+}
+
+/// Writes the distance between src and dst into out.
+/// Returns false, leaving out untouched, when a cube is invalid or the distance doesn't fit in int32_t.
+///
+/// # Safety
+/// out must be null or point to a writable int32_t.
+#[no_mangle]
+pub unsafe extern "C" fn hex_calculate_distance(src: HexCube, dst: HexCube, out: *mut i32) -> bool {
+    let distance = check_cube(src)
+        .zip(check_cube(dst))
+        .and_then(|(src, dst)| neighbor::calculate_checked_distance(src, dst));
+    return write_item(distance, out);
+}
+
+/// Writes the 6 neighbors of cube, in direction order, into out.
+/// Returns false, leaving out untouched, when cube is invalid or a neighbor doesn't fit in int32_t.
+///
+/// # Safety
+/// out must be null or point to at least 6 writable HexCube.
+#[no_mangle]
+pub unsafe extern "C" fn hex_get_neighbors(cube: HexCube, out: *mut HexCube) -> bool {
+    let Some(cube) = check_cube(cube) else {
+        return false;
+    };
+    let mut cubes = [HexCube::from(cube); 6];
+    for (neighbor, direction) in cubes.iter_mut().zip(neighbor::get_cube_direction_vectors()) {
+        let Some(sum) = cube.checked_add(direction) else {
+            return false;
+        };
+        *neighbor = HexCube::from(sum);
+    }
+    write_items(&cubes, out, cubes.len());
+    return true;
+}
+
+/// Writes the cubes within n steps of cube into out, up to capacity, and returns how many there are.
+/// Returns 0 when cube is invalid, n is negative or a cube in range doesn't fit in int32_t.
+///
+/// # Safety
+/// out must be null or point to at least capacity writable HexCube.
+#[no_mangle]
+pub unsafe extern "C" fn hex_get_cubes_within_range(
+    cube: HexCube,
+    n: i32,
+    out: *mut HexCube,
+    capacity: usize,
+) -> usize {
+    let Some(cube) = check_cube(cube) else {
+        return 0;
+    };
+    let Ok(cubes) = neighbor::try_get_cubes_within_range(cube, n) else {
+        return 0;
+    };
+    let count = usize::try_from(n)
+        .ok()
+        .and_then(|n| index::try_calculate_hexagon_size(n).ok());
+    let Some(count) = count else {
+        return 0;
+    };
+    if !out.is_null() {
+        let out = slice::from_raw_parts_mut(out, count.min(capacity));
+        for (item, cube) in out.iter_mut().zip(cubes) {
+            *item = HexCube::from(cube);
+        }
+    }
+    return count;
+}
+
+/// A width x height map where every tile costs 1 to enter.
+/// Returns null when orientation is not a HexOrientation, width or height is above INT32_MAX,
+/// or the tiles can't be allocated.
+#[no_mangle]
+pub extern "C" fn hex_map_new(orientation: u32, width: usize, height: usize) -> *mut HexMap {
+    let Some(orientation) = check_orientation(orientation) else {
+        return core::ptr::null_mut();
+    };
+    // offsets are int32_t, so larger maps would have tiles that can't be addressed
+    if i32::try_from(width).is_err() || i32::try_from(height).is_err() {
+        return core::ptr::null_mut();
+    }
+    let Some(count) = width.checked_mul(height) else {
+        return core::ptr::null_mut();
+    };
+    let mut costs = Vec::new();
+    if costs.try_reserve_exact(count).is_err() {
+        return core::ptr::null_mut();
+    }
+    costs.resize(count, Some(1));
+    let map = HexMap {
+        orientation,
+        size: (width, height),
+        costs,
+    };
+    return Box::into_raw(Box::new(map));
+}
+
+/// # Safety
+/// map must be null or come from hex_map_new and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn hex_map_free(map: *mut HexMap) {
+    if !map.is_null() {
+        drop(Box::from_raw(map));
+    }
+}
+
+/// Sets the cost to enter a tile, 0 marks an obstacle. Returns false outside of the map.
+///
+/// # Safety
+/// map must come from hex_map_new.
+#[no_mangle]
+pub unsafe extern "C" fn hex_map_set_cost(map: *mut HexMap, offset: HexOffset, cost: u32) -> bool {
+    let map = &mut *map;
+    let Some(index) = index::convert_offset_to_index(map.size, Offset::from(offset)) else {
+        return false;
+    };
+    map.costs[index] = Some(cost).filter(|cost| *cost > 0);
+    return true;
+}
+
+/// The cost to enter a tile, 0 for obstacles and tiles outside of the map.
+///
+/// # Safety
+/// map must come from hex_map_new.
+#[no_mangle]
+pub unsafe extern "C" fn hex_map_get_cost(map: *const HexMap, offset: HexOffset) -> u32 {
+    let map = &*map;
+    let index = index::convert_offset_to_index(map.size, Offset::from(offset));
+    return index.and_then(|index| map.costs[index]).unwrap_or(0);
+}
+
+/// Finds the cheapest path from start to goal, both included, with A*.
+/// Writes the path into out up to capacity and its total cost into total_cost when not null.
+/// Returns the path length, or 0 when there is no path.
+///
+/// # Safety
+/// map must come from hex_map_new, out must be null or point to at least capacity writable HexOffset,
+/// total_cost must be null or point to a writable uint32_t.
+#[no_mangle]
+pub unsafe extern "C" fn hex_map_find_path(
+    map: *const HexMap,
+    start: HexOffset,
+    goal: HexOffset,
+    out: *mut HexOffset,
+    capacity: usize,
+    total_cost: *mut u32,
+) -> usize {
+    let map = &*map;
+    let cost = |offset| {
+        let index = index::convert_offset_to_index(map.size, offset)?;
+        return map.costs[index];
+    };
+    let start = Offset::from(start);
+    let goal = Offset::from(goal);
+    let Some((path, distance)) =
+        crate::path::find_path(map.orientation, map.size, start, goal, cost)
+    else {
+        return 0;
+    };
+    if !total_cost.is_null() {
+        *total_cost = distance;
+    }
+    let path: Vec<HexOffset> = path.into_iter().map(HexOffset::from).collect();
+    return write_items(&path, out, capacity);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORIGIN: HexCube = HexCube { q: 0, r: 0, s: 0 };
+
+    #[test]
+    fn conversions() {
+        let size = HexPoint { x: 42.0, y: 30.0 };
+        for orientation in [
+            HexOrientation::HexFlat as u32,
+            HexOrientation::HexPointy as u32,
+        ] {
+            unsafe {
+                let offset = HexOffset { q: 3, r: -2 };
+                let mut cube = ORIGIN;
+                assert!(hex_convert_offset_to_cube(orientation, offset, &mut cube));
+                let mut back = HexOffset { q: 0, r: 0 };
+                assert!(hex_convert_cube_to_offset(orientation, cube, &mut back));
+                assert_eq!(back, offset);
+
+                let mut axial = HexAxial { q: 0, r: 0 };
+                assert!(hex_convert_cube_to_axial(cube, &mut axial));
+                let mut from_axial = ORIGIN;
+                assert!(hex_convert_axial_to_cube(axial, &mut from_axial));
+                assert_eq!(from_axial, cube);
+
+                let mut point = HexPoint { x: 0.0, y: 0.0 };
+                assert!(hex_convert_offset_to_point(
+                    orientation,
+                    size,
+                    offset,
+                    &mut point
+                ));
+                let mut picked = HexOffset { q: 0, r: 0 };
+                assert!(hex_convert_point_to_offset(
+                    orientation,
+                    size,
+                    point,
+                    &mut picked
+                ));
+                assert_eq!(picked, offset);
+            }
+        }
+    }
+
+    // invalid or extreme input from C is reported instead of panicking
+    #[test]
+    fn invalid() {
+        let size = HexPoint { x: 42.0, y: 30.0 };
+        let invalid = HexCube { q: 1, r: 1, s: 5 };
+        let extreme = HexCube {
+            q: i32::MAX,
+            r: i32::MAX,
+            s: 2,
+        };
+        let edge = HexCube {
+            q: i32::MAX,
+            r: -i32::MAX,
+            s: 0,
+        };
+        unsafe {
+            let offset = HexOffset { q: 0, r: 0 };
+            let mut cube = ORIGIN;
+            assert!(!hex_convert_offset_to_cube(2, offset, &mut cube));
+            assert!(!hex_convert_cube_to_offset(
+                u32::MAX,
+                ORIGIN,
+                core::ptr::null_mut()
+            ));
+            assert!(!hex_convert_offset_to_point(
+                2,
+                size,
+                offset,
+                core::ptr::null_mut()
+            ));
+            let point = HexPoint { x: 0.0, y: 0.0 };
+            assert!(!hex_convert_point_to_offset(
+                2,
+                size,
+                point,
+                core::ptr::null_mut()
+            ));
+        }
+        for orientation in [
+            HexOrientation::HexFlat as u32,
+            HexOrientation::HexPointy as u32,
+        ] {
+            unsafe {
+                let offset = HexOffset {
+                    q: i32::MIN,
+                    r: i32::MIN,
+                };
+                assert!(!hex_convert_offset_to_cube(
+                    orientation,
+                    offset,
+                    core::ptr::null_mut()
+                ));
+                assert!(!hex_convert_offset_to_point(
+                    orientation,
+                    size,
+                    offset,
+                    core::ptr::null_mut()
+                ));
+                assert!(!hex_convert_cube_to_offset(
+                    orientation,
+                    invalid,
+                    core::ptr::null_mut()
+                ));
+                assert!(!hex_convert_cube_to_offset(
+                    orientation,
+                    extreme,
+                    core::ptr::null_mut()
+                ));
+
+                let mut out = HexOffset { q: 7, r: 7 };
+                for point in [
+                    HexPoint {
+                        x: f64::NAN,
+                        y: 0.0,
+                    },
+                    HexPoint {
+                        x: 0.0,
+                        y: f64::INFINITY,
+                    },
+                    HexPoint { x: 1e300, y: 0.0 },
+                    HexPoint {
+                        x: 42.0 * 3e9,
+                        y: 0.0,
+                    },
+                ] {
+                    assert!(!hex_convert_point_to_offset(
+                        orientation,
+                        size,
+                        point,
+                        &mut out
+                    ));
+                }
+                let zero = HexPoint { x: 0.0, y: 30.0 };
+                assert!(!hex_convert_point_to_offset(
+                    orientation,
+                    zero,
+                    HexPoint { x: 0.0, y: 0.0 },
+                    &mut out
+                ));
+                assert_eq!(out, HexOffset { q: 7, r: 7 });
+            }
+        }
+        unsafe {
+            let mut axial = HexAxial { q: 0, r: 0 };
+            assert!(!hex_convert_cube_to_axial(invalid, &mut axial));
+            let axial = HexAxial { q: i32::MIN, r: 0 };
+            assert!(!hex_convert_axial_to_cube(axial, core::ptr::null_mut()));
+
+            let mut distance = 0;
+            assert!(!hex_calculate_distance(invalid, ORIGIN, &mut distance));
+            assert!(!hex_calculate_distance(
+                edge,
+                HexCube { q: -1, r: 1, s: 0 },
+                &mut distance
+            ));
+            assert!(hex_calculate_distance(edge, ORIGIN, &mut distance));
+            assert_eq!(distance, i32::MAX);
+
+            let mut neighbors = [ORIGIN; 6];
+            assert!(!hex_get_neighbors(invalid, neighbors.as_mut_ptr()));
+            assert!(!hex_get_neighbors(edge, neighbors.as_mut_ptr()));
+            assert_eq!(neighbors, [ORIGIN; 6]);
+
+            assert_eq!(
+                hex_get_cubes_within_range(invalid, 1, core::ptr::null_mut(), 0),
+                0
+            );
+            assert_eq!(
+                hex_get_cubes_within_range(ORIGIN, -1, core::ptr::null_mut(), 0),
+                0
+            );
+            assert_eq!(
+                hex_get_cubes_within_range(edge, 1, core::ptr::null_mut(), 0),
+                0
+            );
+            let mut cubes = [ORIGIN; 4];
+            let count = hex_get_cubes_within_range(ORIGIN, i32::MAX, cubes.as_mut_ptr(), 4);
+            assert_eq!(count, 0);
+            let count = hex_get_cubes_within_range(ORIGIN, i32::MAX / 2, cubes.as_mut_ptr(), 4);
+            assert_eq!(count, index::calculate_hexagon_size(i32::MAX as usize / 2));
+        }
+    }
+
+    #[test]
+    fn buffers() {
+        let cube = HexCube { q: 1, r: -1, s: 0 };
+        let mut neighbors = [ORIGIN; 6];
+        unsafe {
+            assert!(hex_get_neighbors(cube, neighbors.as_mut_ptr()));
+            for other in neighbors {
+                let mut distance = 0;
+                assert!(hex_calculate_distance(cube, other, &mut distance));
+                assert_eq!(distance, 1);
+            }
+        }
+
+        let count = unsafe { hex_get_cubes_within_range(cube, 2, core::ptr::null_mut(), 0) };
+        assert_eq!(count, 19);
+        let mut cubes = vec![ORIGIN; 4];
+        let count = unsafe { hex_get_cubes_within_range(cube, 2, cubes.as_mut_ptr(), cubes.len()) };
+        assert_eq!(count, 19);
+        assert!(cubes.iter().all(|other| {
+            let (src, dst) = (Cube::from(cube), Cube::from(*other));
+            return neighbor::calculate_distance(src, dst) <= 2;
+        }));
+        assert_ne!(cubes[1], ORIGIN);
+
+        // a hostile n only reports the count, nothing is allocated
+        let count =
+            unsafe { hex_get_cubes_within_range(ORIGIN, 1 << 20, core::ptr::null_mut(), 0) };
+        assert_eq!(count, index::calculate_hexagon_size(1 << 20));
+    }
+
+    #[test]
+    fn map() {
+        let too_wide = i32::MAX as usize + 1;
+        assert!(hex_map_new(2, 8, 6).is_null());
+        assert!(hex_map_new(HexOrientation::HexFlat as u32, too_wide, 1).is_null());
+        assert!(hex_map_new(HexOrientation::HexFlat as u32, usize::MAX, 2).is_null());
+        let (width, height) = (i32::MAX as usize, i32::MAX as usize);
+        assert!(hex_map_new(HexOrientation::HexFlat as u32, width, height).is_null());
+
+        let map = hex_map_new(HexOrientation::HexPointy as u32, 8, 6);
+        unsafe {
+            for r in 0..5 {
+                assert!(hex_map_set_cost(map, HexOffset { q: 4, r }, 0));
+            }
+            assert!(!hex_map_set_cost(map, HexOffset { q: 8, r: 0 }, 1));
+            assert_eq!(hex_map_get_cost(map, HexOffset { q: 4, r: 0 }), 0);
+            assert_eq!(hex_map_get_cost(map, HexOffset { q: 0, r: 0 }), 1);
+
+            let start = HexOffset { q: 0, r: 0 };
+            let goal = HexOffset { q: 7, r: 0 };
+            let mut total_cost = 0;
+            let length =
+                hex_map_find_path(map, start, goal, core::ptr::null_mut(), 0, &mut total_cost);
+            assert_eq!(length as u32, total_cost + 1);
+            let mut path = vec![start; length];
+            assert_eq!(
+                hex_map_find_path(
+                    map,
+                    start,
+                    goal,
+                    path.as_mut_ptr(),
+                    length,
+                    core::ptr::null_mut()
+                ),
+                length
+            );
+            assert_eq!(path[0], start);
+            assert_eq!(path[length - 1], goal);
+            assert!(path.iter().any(|offset| offset.r == 5));
+
+            hex_map_set_cost(map, HexOffset { q: 4, r: 5 }, 0);
+            assert_eq!(
+                hex_map_find_path(
+                    map,
+                    start,
+                    goal,
+                    core::ptr::null_mut(),
+                    0,
+                    core::ptr::null_mut()
+                ),
+                0
+            );
+            hex_map_free(map);
+        }
+    }
+}
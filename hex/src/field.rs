@@ -0,0 +1,157 @@
+// distance fields over a width x height offset map, aka dijkstra maps
+// fields are stored row by row, the same order as index::convert_offset_to_index
+
+use crate::{shared::index::*, structs::*};
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::{vec, vec::Vec};
+use core::cmp::Reverse;
+
+// the number of steps to the nearest source, None if unreachable
+// sources are always reachable, and sources outside of the map are ignored
+pub fn calculate_distance_field<P>(
+    orientation: Orientation,
+    size: (usize, usize),
+    sources: &[Offset<i32>],
+    is_passable: P,
+) -> Vec<Option<u32>>
+where
+    P: Fn(Offset<i32>) -> bool,
+{
+    let mut field = vec![None; size.0 * size.1];
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if let Some(index) = convert_offset_to_index(size, *source) {
+            field[index] = Some(0);
+            queue.push_back((*source, 0));
+        }
+    }
+    while let Some((offset, distance)) = queue.pop_front() {
+        for neighbor in orientation.get_neighbor_offsets(offset) {
+            let Some(index) = convert_offset_to_index(size, neighbor) else {
+                continue;
+            };
+            if field[index].is_some() || !is_passable(neighbor) {
+                continue;
+            }
+            field[index] = Some(distance + 1);
+            queue.push_back((neighbor, distance + 1));
+        }
+    }
+    return field;
+}
+
+// the cheapest total cost to the nearest source, where cost is paid when entering a tile
+// None from cost marks an obstacle, the result is None if unreachable
+pub fn calculate_weighted_distance_field<C>(
+    orientation: Orientation,
+    size: (usize, usize),
+    sources: &[Offset<i32>],
+    cost: C,
+) -> Vec<Option<u32>>
+where
+    C: Fn(Offset<i32>) -> Option<u32>,
+{
+    let mut field: Vec<Option<u32>> = vec![None; size.0 * size.1];
+    let mut heap = BinaryHeap::new();
+    for source in sources {
+        if let Some(index) = convert_offset_to_index(size, *source) {
+            field[index] = Some(0);
+            heap.push(Reverse((0, index)));
+        }
+    }
+    while let Some(Reverse((distance, index))) = heap.pop() {
+        if field[index].is_some_and(|best| best < distance) {
+            continue;
+        }
+        let offset = convert_index_to_offset(size, index).unwrap();
+        for neighbor in orientation.get_neighbor_offsets(offset) {
+            let Some(next) = convert_offset_to_index(size, neighbor) else {
+                continue;
+            };
+            let Some(cost) = cost(neighbor) else {
+                continue;
+            };
+            let distance = distance.saturating_add(cost);
+            if field[next].is_some_and(|best| best <= distance) {
+                continue;
+            }
+            field[next] = Some(distance);
+            heap.push(Reverse((distance, next)));
+        }
+    }
+    return field;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::neighbor::*;
+
+    const SIZE: (usize, usize) = (8, 6);
+    const ORIENTATIONS: [Orientation; 2] = [Orientation::Flat, Orientation::Pointy];
+
+    #[test]
+    fn open_map() {
+        let sources = [Offset { q: 1, r: 1 }, Offset { q: 6, r: 4 }];
+        for orientation in ORIENTATIONS {
+            let field = calculate_distance_field(orientation, SIZE, &sources, |_| true);
+            let weighted =
+                calculate_weighted_distance_field(orientation, SIZE, &sources, |_| Some(1));
+            assert_eq!(field, weighted);
+            for (index, distance) in field.iter().enumerate() {
+                let offset = convert_index_to_offset(SIZE, index).unwrap();
+                let cube = orientation.convert_offset_to_cube(offset);
+                let exp = sources
+                    .iter()
+                    .map(|source| {
+                        calculate_distance(cube, orientation.convert_offset_to_cube(*source))
+                    })
+                    .min()
+                    .unwrap();
+                assert_eq!(*distance, Some(exp as u32), "{:?}", offset);
+            }
+        }
+    }
+
+    #[test]
+    fn obstacles() {
+        // a wall at q == 3, with a gap at the top row
+        let is_passable = |offset: Offset<i32>| offset.q != 3 || offset.r == 5;
+        let cost = |offset: Offset<i32>| is_passable(offset).then_some(1);
+        let sources = [Offset { q: 0, r: 0 }];
+        for orientation in ORIENTATIONS {
+            let field = calculate_distance_field(orientation, SIZE, &sources, is_passable);
+            let weighted = calculate_weighted_distance_field(orientation, SIZE, &sources, cost);
+            assert_eq!(field, weighted);
+            let wall = convert_offset_to_index(SIZE, Offset { q: 3, r: 0 }).unwrap();
+            assert_eq!(field[wall], None);
+            let behind = convert_offset_to_index(SIZE, Offset { q: 4, r: 0 }).unwrap();
+            assert!(field[behind].unwrap() > 4 + 5, "{:?}", field[behind]);
+        }
+
+        let sealed =
+            calculate_distance_field(Orientation::Flat, SIZE, &sources, |offset| offset.q != 3);
+        let behind = convert_offset_to_index(SIZE, Offset { q: 4, r: 0 }).unwrap();
+        assert_eq!(sealed[behind], None);
+    }
+
+    #[test]
+    fn weights() {
+        // going through the middle row costs 10, so going around is cheaper
+        let cost = |offset: Offset<i32>| Some(if offset.r == 2 && offset.q > 0 { 10 } else { 1 });
+        let field = calculate_weighted_distance_field(
+            Orientation::Pointy,
+            SIZE,
+            &[Offset { q: 2, r: 0 }],
+            cost,
+        );
+        let index = convert_offset_to_index(SIZE, Offset { q: 2, r: 4 }).unwrap();
+        let direct =
+            calculate_distance_field(Orientation::Pointy, SIZE, &[Offset { q: 2, r: 0 }], |_| {
+                true
+            });
+        assert_eq!(direct[index], Some(4));
+        assert!(field[index].unwrap() < 4 + 9);
+        assert!(field[index].unwrap() > 4);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{flat::coordinate::*, shared::neighbor::*, structs::*};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+pub use crate::shared::boundary::*;
+
+// one polygon per loop of get_boundary_loops, with the same orientation
+pub fn get_boundary_polygons<I, F>(size: (F, F), region: &HashSet<Cube<I>>) -> Vec<Vec<(F, F)>>
+where
+    I: Number + Hash + num::cast::AsPrimitive<F>,
+    F: 'static + num::Float,
+    i32: num::cast::AsPrimitive<F>,
+{
+    return get_boundary_loops(region)
+        .into_iter()
+        .map(|boundary| {
+            boundary
+                .into_iter()
+                .map(|(cube, i)| convert_axial_to_corners(size, convert_cube_to_axial(cube))[i])
+                .collect()
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TILE_SIZE: (f32, f32) = (42.0, 30.0);
+    const ORIGIN: Cube<i32> = Cube { q: 0, r: 0, s: 0 };
+
+    // positive when counterclockwise with y pointing up
+    fn calculate_area(polygon: &[(f32, f32)]) -> f32 {
+        let mut area = 0.0;
+        for (index, a) in polygon.iter().enumerate() {
+            let b = polygon[(index + 1) % polygon.len()];
+            area += a.0 * b.1 - b.0 * a.1;
+        }
+        return area / 2.0;
+    }
+
+    #[test]
+    fn polygons() {
+        let single = get_boundary_polygons(TILE_SIZE, &HashSet::from([ORIGIN]));
+        assert_eq!(single.len(), 1);
+        let area = calculate_area(&single[0]);
+        assert!(area > 0.0);
+
+        let region: HashSet<_> = get_nth_nearest_cubes(ORIGIN, 1).collect();
+        let mut polygons = get_boundary_polygons(TILE_SIZE, &region);
+        polygons.sort_by_key(|polygon| polygon.len());
+        assert_eq!(polygons.len(), 2);
+        assert!((calculate_area(&polygons[0]) + area).abs() < 0.01);
+        assert!((calculate_area(&polygons[1]) - 7.0 * area).abs() < 0.01);
+    }
+}
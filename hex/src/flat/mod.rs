@@ -1,2 +1,16 @@
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod boundary;
 pub mod coordinate;
+pub mod edge;
+pub mod shape;
+pub mod triangle;
+pub use crate::shared::chunk;
+pub use crate::shared::index;
+pub use crate::shared::line;
 pub use crate::shared::neighbor;
+#[cfg(feature = "rand")]
+pub use crate::shared::random;
+#[cfg(feature = "std")]
+pub use crate::shared::region;
+pub use crate::shared::round;
@@ -0,0 +1,33 @@
+use crate::{flat::coordinate::*, shared::neighbor::*, structs::*};
+
+pub use crate::shared::shape::*;
+
+// row by row, same order as convert_index_to_offset
+pub fn get_rectangle_cubes<T>(size: (T, T)) -> impl Iterator<Item = Cube<T>>
+where
+    T: Number + num::Integer,
+    core::ops::Range<T>: Iterator<Item = T>,
+{
+    (num::zero()..size.1).flat_map(move |r| {
+        (num::zero()..size.0)
+            .map(move |q| convert_axial_to_cube(convert_offset_to_axial(Offset { q, r })))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::index::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn rectangle() {
+        const SIZE: (usize, usize) = (4, 3);
+        let result: Vec<_> = get_rectangle_cubes((4, 3)).collect();
+        assert_eq!(result.len(), 12);
+        for (index, cube) in result.into_iter().enumerate() {
+            let offset: Offset<i32> = convert_axial_to_offset(convert_cube_to_axial(cube));
+            assert_eq!(convert_offset_to_index(SIZE, offset), Some(index));
+        }
+    }
+}
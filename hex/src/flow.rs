@@ -0,0 +1,128 @@
+// flow fields over a width x height offset map, for many units heading to the same goals
+// https://www.redblobgames.com/blog/2024-04-27-flow-field-pathfinding/
+
+use crate::{field::*, shared::index::*, structs::*};
+use alloc::vec::Vec;
+
+pub struct FlowField {
+    orientation: Orientation,
+    size: (usize, usize),
+    integration: Vec<Option<u32>>,
+    directions: Vec<Option<usize>>,
+}
+
+impl FlowField {
+    // cost is paid when entering a tile, None from cost marks an obstacle
+    pub fn new<C>(
+        orientation: Orientation,
+        size: (usize, usize),
+        goals: &[Offset<i32>],
+        cost: C,
+    ) -> Self
+    where
+        C: Fn(Offset<i32>) -> Option<u32>,
+    {
+        let integration = calculate_weighted_distance_field(orientation, size, goals, cost);
+        let directions = (0..integration.len())
+            .map(|index| {
+                let current = integration[index]?;
+                let offset = convert_index_to_offset::<i32>(size, index).unwrap();
+                let mut best = None;
+                for (direction, neighbor) in orientation
+                    .get_neighbor_offsets(offset)
+                    .into_iter()
+                    .enumerate()
+                {
+                    let Some(neighbor) = convert_offset_to_index(size, neighbor) else {
+                        continue;
+                    };
+                    let Some(cost) = integration[neighbor] else {
+                        continue;
+                    };
+                    if cost < best.map_or(current, |(_, cost)| cost) {
+                        best = Some((direction, cost));
+                    }
+                }
+                return best.map(|(direction, _)| direction);
+            })
+            .collect();
+        return FlowField {
+            orientation,
+            size,
+            integration,
+            directions,
+        };
+    }
+
+    // the cheapest total cost to the nearest goal, None if unreachable
+    pub fn get_integration(&self, offset: Offset<i32>) -> Option<u32> {
+        return convert_offset_to_index(self.size, offset)
+            .and_then(|index| self.integration[index]);
+    }
+
+    // index into get_cube_direction_vectors, None at goals and unreachable tiles
+    pub fn get_direction(&self, offset: Offset<i32>) -> Option<usize> {
+        return convert_offset_to_index(self.size, offset).and_then(|index| self.directions[index]);
+    }
+
+    pub fn get_next_offset(&self, offset: Offset<i32>) -> Option<Offset<i32>> {
+        let direction = self.get_direction(offset)?;
+        return Some(self.orientation.get_neighbor_offsets(offset)[direction]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::neighbor::*;
+
+    const SIZE: (usize, usize) = (12, 10);
+
+    fn cost(offset: Offset<i32>) -> Option<u32> {
+        if offset.q == 5 && offset.r != 8 {
+            return None;
+        }
+        return Some(if offset.r == 3 { 4 } else { 1 });
+    }
+
+    #[test]
+    fn follow() {
+        for orientation in [Orientation::Flat, Orientation::Pointy] {
+            let goal = Offset { q: 10, r: 2 };
+            let field = FlowField::new(orientation, SIZE, &[goal], cost);
+            assert_eq!(field.get_integration(goal), Some(0));
+            assert_eq!(field.get_direction(goal), None);
+            assert_eq!(field.get_direction(Offset { q: 5, r: 0 }), None);
+            assert_eq!(field.get_direction(Offset { q: 12, r: 0 }), None);
+
+            for index in 0..SIZE.0 * SIZE.1 {
+                let mut offset = convert_index_to_offset(SIZE, index).unwrap();
+                if cost(offset).is_none() {
+                    continue;
+                }
+                let mut steps = 0;
+                while let Some(next) = field.get_next_offset(offset) {
+                    let a = orientation.convert_offset_to_cube(offset);
+                    let b = orientation.convert_offset_to_cube(next);
+                    assert_eq!(calculate_distance(a, b), 1);
+                    assert!(field.get_integration(next) < field.get_integration(offset));
+                    offset = next;
+                    steps += 1;
+                }
+                assert_eq!(offset, goal, "{} steps", steps);
+            }
+        }
+    }
+
+    #[test]
+    fn direction() {
+        let field = FlowField::new(Orientation::Flat, SIZE, &[Offset { q: 3, r: 5 }], |_| {
+            Some(1)
+        });
+        let vectors = get_cube_direction_vectors::<i32>();
+        let from = Orientation::Flat.convert_offset_to_cube(Offset { q: 3, r: 3 });
+        let to = Orientation::Flat.convert_offset_to_cube(Offset { q: 3, r: 4 });
+        let direction = field.get_direction(Offset { q: 3, r: 3 }).unwrap();
+        assert_eq!(from + vectors[direction], to);
+    }
+}
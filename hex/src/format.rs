@@ -0,0 +1,179 @@
+// coordinates as strings, whitespace around components is ignored
+//
+// cube    "(q,r,s)"  e.g. "(1,-2,1)"
+// axial   "q{q}r{r}" e.g. "q1r-2"
+// offset  "[q,r]"    e.g. "[3,4]"
+
+use crate::{error::*, structs::*};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+fn parse_component<T: FromStr>(text: &str, name: char) -> Result<T, ParseCoordinateError> {
+    return text
+        .trim()
+        .parse()
+        .map_err(|_| ParseCoordinateError::Component(name));
+}
+
+// the comma separated parts between open and close
+fn split_bracket(text: &str, open: char, close: char) -> Result<Vec<&str>, ParseCoordinateError> {
+    let inner = text
+        .trim()
+        .strip_prefix(open)
+        .and_then(|text| text.strip_suffix(close))
+        .ok_or(ParseCoordinateError::Syntax)?;
+    return Ok(inner.split(',').collect());
+}
+
+impl<T: fmt::Display> fmt::Display for Cube<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{},{})", self.q, self.r, self.s)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Axial<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "q{}r{}", self.q, self.r)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Offset<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{},{}]", self.q, self.r)
+    }
+}
+
+// does not check q + r + s == 0, see from_str_strict
+impl<T: FromStr> FromStr for Cube<T> {
+    type Err = ParseCoordinateError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let [q, r, s] = split_bracket(text, '(', ')')?[..] else {
+            return Err(ParseCoordinateError::Syntax);
+        };
+        return Ok(Cube {
+            q: parse_component(q, 'q')?,
+            r: parse_component(r, 'r')?,
+            s: parse_component(s, 's')?,
+        });
+    }
+}
+
+impl<T: FromStr + num::Num + num::CheckedAdd + Clone> Cube<T> {
+    pub fn from_str_strict(text: &str) -> Result<Self, ParseCoordinateError> {
+        let cube: Self = text.parse()?;
+        if !cube.is_valid() {
+            return Err(ParseCoordinateError::InvalidCube);
+        }
+        return Ok(cube);
+    }
+}
+
+impl<T: FromStr> FromStr for Axial<T> {
+    type Err = ParseCoordinateError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text
+            .trim()
+            .strip_prefix('q')
+            .ok_or(ParseCoordinateError::Syntax)?;
+        let (q, r) = text.split_once('r').ok_or(ParseCoordinateError::Syntax)?;
+        return Ok(Axial {
+            q: parse_component(q, 'q')?,
+            r: parse_component(r, 'r')?,
+        });
+    }
+}
+
+impl<T: FromStr> FromStr for Offset<T> {
+    type Err = ParseCoordinateError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let [q, r] = split_bracket(text, '[', ']')?[..] else {
+            return Err(ParseCoordinateError::Syntax);
+        };
+        return Ok(Offset {
+            q: parse_component(q, 'q')?,
+            r: parse_component(r, 'r')?,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn round_trip() {
+        let cube = Cube { q: 1, r: -2, s: 1 };
+        assert_eq!(cube.to_string(), "(1,-2,1)");
+        assert_eq!("(1,-2,1)".parse(), Ok(cube));
+        assert_eq!(" ( 1, -2 ,1 ) ".parse(), Ok(cube));
+
+        let axial = Axial { q: 1, r: -2 };
+        assert_eq!(axial.to_string(), "q1r-2");
+        assert_eq!("q1r-2".parse(), Ok(axial));
+        assert_eq!("q1.5r-2".parse(), Ok(Axial { q: 1.5, r: -2.0 }));
+
+        let offset = Offset { q: 3, r: 4 };
+        assert_eq!(offset.to_string(), "[3,4]");
+        assert_eq!("[3, 4]".parse(), Ok(offset));
+    }
+
+    #[test]
+    fn errors() {
+        type E = ParseCoordinateError;
+        assert_eq!("1,-2,1".parse::<Cube<i32>>(), Err(E::Syntax));
+        assert_eq!("(1,-2)".parse::<Cube<i32>>(), Err(E::Syntax));
+        assert_eq!("(1,x,1)".parse::<Cube<i32>>(), Err(E::Component('r')));
+        assert_eq!("(1,-2,)".parse::<Cube<i32>>(), Err(E::Component('s')));
+        assert_eq!("r1q2".parse::<Axial<i32>>(), Err(E::Syntax));
+        assert_eq!("q1".parse::<Axial<i32>>(), Err(E::Syntax));
+        assert_eq!("qr2".parse::<Axial<i32>>(), Err(E::Component('q')));
+        assert_eq!("[3,4,5]".parse::<Offset<i32>>(), Err(E::Syntax));
+        assert_eq!("[300,4]".parse::<Offset<i8>>(), Err(E::Component('q')));
+    }
+
+    #[test]
+    fn strict() {
+        assert_eq!("(1,2,3)".parse(), Ok(Cube { q: 1, r: 2, s: 3 }));
+        assert_eq!(
+            Cube::<i32>::from_str_strict("(1,2,3)"),
+            Err(ParseCoordinateError::InvalidCube)
+        );
+        assert_eq!(
+            Cube::from_str_strict("(1,2,-3)"),
+            Ok(Cube { q: 1, r: 2, s: -3 })
+        );
+        assert_eq!(
+            Cube::<i32>::from_str_strict("(1,2)"),
+            Err(ParseCoordinateError::Syntax)
+        );
+    }
+
+    #[test]
+    fn strict_limits() {
+        assert_eq!(
+            Cube::<i16>::from_str_strict("(32767,32767,2)"),
+            Err(ParseCoordinateError::InvalidCube)
+        );
+        assert_eq!(
+            Cube::<i16>::from_str_strict("(-32768,-32768,0)"),
+            Err(ParseCoordinateError::InvalidCube)
+        );
+        assert_eq!(
+            Cube::<i16>::from_str_strict("(27307,5461,-32768)"),
+            Ok(Cube {
+                q: 27307,
+                r: 5461,
+                s: i16::MIN
+            })
+        );
+        assert_eq!(
+            Cube::<i16>::from_str_strict("(32768,0,-32768)"),
+            Err(ParseCoordinateError::Component('q'))
+        );
+    }
+}
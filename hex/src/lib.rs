@@ -1,6 +1,33 @@
+#![allow(clippy::needless_return)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+#[cfg(feature = "bevy")]
+pub mod bevy;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod field;
 pub mod flat;
+pub mod flow;
+mod format;
+#[cfg(feature = "std")]
+pub mod map;
+mod orientation;
+#[cfg(feature = "std")]
+pub mod path;
 pub mod pointy;
+#[cfg(feature = "python")]
+pub mod python;
 mod shared;
 mod structs;
+#[cfg(feature = "tiled")]
+pub mod tiled;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+pub use error::*;
 pub use structs::*;
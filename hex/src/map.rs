@@ -0,0 +1,418 @@
+// a compact binary format for hex maps, all integers are little endian
+//
+// magic      4 bytes  "HEXM"
+// version    u8 u8    major then minor, currently 1.0
+//                     readers reject other majors, a minor bump only adds header extensions so newer minors are read
+// orientation u8      0 flat, 1 pointy
+// convention u8       0 for this crate: flat "even-q with y inversed", pointy "odd-r with y inversed"
+// shape      u8       0 rectangle, 1 hexagon
+// flags      u8       bit 0 run length encoding, other bits must be 0
+// dimensions u32 u32  width and height for rectangle, radius and 0 for hexagon
+// extension  u16      length of extra header bytes, skipped by readers that don't know them
+// tiles               rectangle row by row as index::convert_offset_to_index
+//                     hexagon in spiral order around the origin as index::convert_spiral_index_to_cube
+//                     with run length encoding, each run is a u32 count followed by one payload
+
+use crate::{shared::index::*, structs::*};
+use std::fmt;
+use std::io::{self, Read, Write};
+
+pub const MAGIC: [u8; 4] = *b"HEXM";
+pub const VERSION_MAJOR: u8 = 1;
+pub const VERSION_MINOR: u8 = 0;
+// the limit of read_map, enough for a rectangle of 8192 x 8192 tiles
+pub const MAX_TILE_COUNT: usize = 1 << 26;
+const CONVENTION: u8 = 0;
+const FLAG_RUN_LENGTH: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapShape {
+    Rectangle { width: u32, height: u32 },
+    Hexagon { radius: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapHeader {
+    pub orientation: Orientation,
+    pub shape: MapShape,
+    pub run_length: bool,
+}
+
+// how a single tile is stored, implemented for integers and bool
+pub trait TilePayload: Sized {
+    fn write_payload<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+    fn read_payload<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_tile_payload {
+    ($($t:ty),*) => {
+        $(
+            impl TilePayload for $t {
+                fn write_payload<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn read_payload<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut bytes = [0; std::mem::size_of::<$t>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$t>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_tile_payload!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl TilePayload for bool {
+    fn write_payload<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[*self as u8])
+    }
+
+    fn read_payload<R: Read>(reader: &mut R) -> io::Result<Self> {
+        return match u8::read_payload(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid bool")),
+        };
+    }
+}
+
+#[derive(Debug)]
+pub enum MapError {
+    Io(io::Error),
+    Magic,
+    UnsupportedVersion(u8),
+    TooLarge(usize),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Io(error) => write!(f, "io error: {}", error),
+            MapError::Magic => write!(f, "not a hex map"),
+            MapError::UnsupportedVersion(major) => {
+                write!(f, "unsupported major version {}", major)
+            }
+            MapError::TooLarge(count) => write!(f, "too many tiles: {}", count),
+            MapError::Invalid(name) => write!(f, "invalid {}", name),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<io::Error> for MapError {
+    fn from(error: io::Error) -> Self {
+        MapError::Io(error)
+    }
+}
+
+impl MapHeader {
+    // None if the count overflows usize or the tiles don't fit in Offset<i32>
+    pub fn get_tile_count(&self) -> Option<usize> {
+        let limit = i32::MAX as u32;
+        return match self.shape {
+            MapShape::Rectangle { width, height } if width <= limit && height <= limit => {
+                (width as usize).checked_mul(height as usize)
+            }
+            MapShape::Hexagon { radius } if radius <= limit => {
+                try_calculate_hexagon_size(radius as usize).ok()
+            }
+            _ => None,
+        };
+    }
+
+    // the offset of every tile, in the order they are stored, None like get_tile_count
+    pub fn get_offsets(&self) -> Option<Vec<Offset<i32>>> {
+        let count = self.get_tile_count()?;
+        let offsets = match self.shape {
+            MapShape::Rectangle { width, height } => {
+                let size = (width as usize, height as usize);
+                (0..count)
+                    .map(|index| convert_index_to_offset(size, index).unwrap())
+                    .collect()
+            }
+            MapShape::Hexagon { .. } => {
+                let origin = Cube { q: 0, r: 0, s: 0 };
+                (0..count)
+                    .map(|index| convert_spiral_index_to_cube(origin, index))
+                    .map(|cube| self.orientation.convert_cube_to_offset(cube))
+                    .collect()
+            }
+        };
+        return Some(offsets);
+    }
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    return u8::read_payload(reader);
+}
+
+pub fn write_map<W, P>(writer: &mut W, header: &MapHeader, tiles: &[P]) -> Result<(), MapError>
+where
+    W: Write,
+    P: TilePayload + PartialEq,
+{
+    if Some(tiles.len()) != header.get_tile_count() {
+        return Err(MapError::Invalid("tile count"));
+    }
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&[VERSION_MAJOR, VERSION_MINOR])?;
+    let orientation: u8 = match header.orientation {
+        Orientation::Flat => 0,
+        Orientation::Pointy => 1,
+    };
+    let (shape, dimensions): (u8, _) = match header.shape {
+        MapShape::Rectangle { width, height } => (0, (width, height)),
+        MapShape::Hexagon { radius } => (1, (radius, 0)),
+    };
+    let flags = if header.run_length {
+        FLAG_RUN_LENGTH
+    } else {
+        0
+    };
+    writer.write_all(&[orientation, CONVENTION, shape, flags])?;
+    dimensions.0.write_payload(writer)?;
+    dimensions.1.write_payload(writer)?;
+    0u16.write_payload(writer)?;
+
+    if !header.run_length {
+        for tile in tiles {
+            tile.write_payload(writer)?;
+        }
+        return Ok(());
+    }
+    let mut start = 0;
+    while start < tiles.len() {
+        let mut end = start + 1;
+        while end < tiles.len() && end - start < u32::MAX as usize && tiles[end] == tiles[start] {
+            end += 1;
+        }
+        ((end - start) as u32).write_payload(writer)?;
+        tiles[start].write_payload(writer)?;
+        start = end;
+    }
+    return Ok(());
+}
+
+pub fn read_map<R, P>(reader: &mut R) -> Result<(MapHeader, Vec<P>), MapError>
+where
+    R: Read,
+    P: TilePayload + Clone,
+{
+    return read_map_with_limit(reader, MAX_TILE_COUNT);
+}
+
+// maps with more than limit tiles are rejected before any tile is read
+// tiles are only allocated as their payload is read, so a short file can't claim a huge map
+pub fn read_map_with_limit<R, P>(
+    reader: &mut R,
+    limit: usize,
+) -> Result<(MapHeader, Vec<P>), MapError>
+where
+    R: Read,
+    P: TilePayload + Clone,
+{
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(MapError::Magic);
+    }
+    let major = read_u8(reader)?;
+    if major != VERSION_MAJOR {
+        return Err(MapError::UnsupportedVersion(major));
+    }
+    // newer minors only add extension bytes, which are skipped below
+    read_u8(reader)?;
+    let orientation = match read_u8(reader)? {
+        0 => Orientation::Flat,
+        1 => Orientation::Pointy,
+        _ => return Err(MapError::Invalid("orientation")),
+    };
+    if read_u8(reader)? != CONVENTION {
+        return Err(MapError::Invalid("offset convention"));
+    }
+    let shape = read_u8(reader)?;
+    let flags = read_u8(reader)?;
+    if flags & !FLAG_RUN_LENGTH != 0 {
+        return Err(MapError::Invalid("flags"));
+    }
+    let dimensions = (u32::read_payload(reader)?, u32::read_payload(reader)?);
+    let shape = match shape {
+        0 => MapShape::Rectangle {
+            width: dimensions.0,
+            height: dimensions.1,
+        },
+        1 => MapShape::Hexagon {
+            radius: dimensions.0,
+        },
+        _ => return Err(MapError::Invalid("shape")),
+    };
+    let extension = u16::read_payload(reader)?;
+    io::copy(&mut reader.take(extension as u64), &mut io::sink())?;
+
+    let header = MapHeader {
+        orientation,
+        shape,
+        run_length: flags & FLAG_RUN_LENGTH != 0,
+    };
+    let count = header
+        .get_tile_count()
+        .ok_or(MapError::Invalid("dimensions"))?;
+    if count > limit {
+        return Err(MapError::TooLarge(count));
+    }
+    let mut tiles = Vec::new();
+    if !header.run_length {
+        for _ in 0..count {
+            tiles.push(P::read_payload(reader)?);
+        }
+        return Ok((header, tiles));
+    }
+    while tiles.len() < count {
+        let run = u32::read_payload(reader)? as usize;
+        if run == 0 || run > count - tiles.len() {
+            return Err(MapError::Invalid("run length"));
+        }
+        let tile = P::read_payload(reader)?;
+        tiles.resize(tiles.len() + run, tile);
+    }
+    return Ok((header, tiles));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip<P: TilePayload + PartialEq + Clone + fmt::Debug>(
+        header: MapHeader,
+        tiles: Vec<P>,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_map(&mut bytes, &header, &tiles).unwrap();
+        let (result, result_tiles) = read_map::<_, P>(&mut bytes.as_slice()).unwrap();
+        assert_eq!(result, header);
+        assert_eq!(result_tiles, tiles);
+        return bytes;
+    }
+
+    #[test]
+    fn rectangle() {
+        let tiles: Vec<u16> = (0..12).map(|i| i * 100).collect();
+        for orientation in [Orientation::Flat, Orientation::Pointy] {
+            let header = MapHeader {
+                orientation,
+                shape: MapShape::Rectangle {
+                    width: 4,
+                    height: 3,
+                },
+                run_length: false,
+            };
+            let bytes = round_trip(header, tiles.clone());
+            assert_eq!(bytes.len(), 20 + 12 * 2);
+            assert_eq!(&bytes[0..4], b"HEXM");
+            assert_eq!(header.get_offsets().unwrap()[5], Offset { q: 1, r: 1 });
+        }
+    }
+
+    #[test]
+    fn run_length() {
+        let header = MapHeader {
+            orientation: Orientation::Pointy,
+            shape: MapShape::Hexagon { radius: 20 },
+            run_length: true,
+        };
+        let count = header.get_tile_count().unwrap();
+        let tiles: Vec<u8> = (0..count).map(|i| (i / 500) as u8).collect();
+        let bytes = round_trip(header, tiles);
+        assert_eq!(bytes.len(), 20 + 3 * 5);
+
+        let offsets = header.get_offsets().unwrap();
+        assert_eq!(offsets.len(), count);
+        assert_eq!(offsets[0], Offset { q: 0, r: 0 });
+
+        let bools = round_trip(header, vec![true; count]);
+        assert_eq!(bools.len(), 20 + 5);
+    }
+
+    #[test]
+    fn invalid() {
+        let header = MapHeader {
+            orientation: Orientation::Flat,
+            shape: MapShape::Rectangle {
+                width: 2,
+                height: 2,
+            },
+            run_length: false,
+        };
+        let result = write_map(&mut Vec::new(), &header, &[1u8, 2, 3]);
+        assert!(matches!(result, Err(MapError::Invalid("tile count"))));
+
+        let mut bytes = Vec::new();
+        write_map(&mut bytes, &header, &[1u8, 2, 3, 4]).unwrap();
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        let result = read_map::<_, u8>(&mut newer.as_slice());
+        assert!(matches!(result, Err(MapError::UnsupportedVersion(2))));
+
+        let mut convention = bytes.clone();
+        convention[7] = 1;
+        let result = read_map::<_, u8>(&mut convention.as_slice());
+        assert!(matches!(
+            result,
+            Err(MapError::Invalid("offset convention"))
+        ));
+
+        let result = read_map::<_, u8>(&mut &bytes[..bytes.len() - 1]);
+        assert!(matches!(result, Err(MapError::Io(_))));
+
+        // a newer minor with unknown header extensions is read
+        let mut extended = bytes[..18].to_vec();
+        extended[5] = 7;
+        extended.extend([3, 0, 9, 9, 9]);
+        extended.extend(&bytes[20..]);
+        let (_, tiles) = read_map::<_, u8>(&mut extended.as_slice()).unwrap();
+        assert_eq!(tiles, vec![1, 2, 3, 4]);
+    }
+
+    fn new_header_bytes(shape: u8, dimensions: (u32, u32), flags: u8) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([VERSION_MAJOR, VERSION_MINOR, 0, CONVENTION, shape, flags]);
+        bytes.extend(dimensions.0.to_le_bytes());
+        bytes.extend(dimensions.1.to_le_bytes());
+        bytes.extend([0, 0]);
+        return bytes;
+    }
+
+    #[test]
+    fn hostile() {
+        let bytes = new_header_bytes(1, (u32::MAX, 0), 0);
+        let result = read_map::<_, u8>(&mut bytes.as_slice());
+        assert!(matches!(result, Err(MapError::Invalid("dimensions"))));
+        let bytes = new_header_bytes(0, (u32::MAX, u32::MAX), 0);
+        let result = read_map::<_, u8>(&mut bytes.as_slice());
+        assert!(matches!(result, Err(MapError::Invalid("dimensions"))));
+
+        // a single run covering 65536 x 65536 tiles
+        let mut bytes = new_header_bytes(0, (65536, 65536), FLAG_RUN_LENGTH);
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.push(1);
+        let result = read_map::<_, u8>(&mut bytes.as_slice());
+        assert!(matches!(result, Err(MapError::TooLarge(_))));
+
+        let mut bytes = new_header_bytes(0, (4, 4), FLAG_RUN_LENGTH);
+        bytes.extend(16u32.to_le_bytes());
+        bytes.push(1);
+        let result = read_map_with_limit::<_, u8>(&mut bytes.as_slice(), 15);
+        assert!(matches!(result, Err(MapError::TooLarge(16))));
+        let (_, tiles) = read_map_with_limit::<_, u8>(&mut bytes.as_slice(), 16).unwrap();
+        assert_eq!(tiles, vec![1; 16]);
+
+        // the count is known but the tiles are missing
+        let bytes = new_header_bytes(0, (8192, 8192), 0);
+        let result = read_map::<_, u8>(&mut bytes.as_slice());
+        assert!(matches!(result, Err(MapError::Io(_))));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{error::*, flat, pointy, shared::neighbor::*, structs::*};
+use num::cast::AsPrimitive;
+use num::traits::CheckedNeg;
+use num::{CheckedAdd, CheckedSub};
+
+// dispatches to flat or pointy, for maps whose orientation is only known at runtime
+impl Orientation {
+    pub fn convert_offset_to_cube<T>(&self, offset: Offset<T>) -> Cube<T>
+    where
+        T: num::Integer + num::Signed + Copy,
+    {
+        let axial = match self {
+            Orientation::Flat => flat::coordinate::convert_offset_to_axial(offset),
+            Orientation::Pointy => pointy::coordinate::convert_offset_to_axial(offset),
+        };
+        return flat::coordinate::convert_axial_to_cube(axial);
+    }
+
+    pub fn convert_cube_to_offset<T>(&self, cube: Cube<T>) -> Offset<T>
+    where
+        T: num::Integer + num::Signed + Copy,
+    {
+        let axial = flat::coordinate::convert_cube_to_axial(cube);
+        return match self {
+            Orientation::Flat => flat::coordinate::convert_axial_to_offset(axial),
+            Orientation::Pointy => pointy::coordinate::convert_axial_to_offset(axial),
+        };
+    }
+
+    pub fn try_convert_offset_to_cube<T>(&self, offset: Offset<T>) -> Result<Cube<T>, OverflowError>
+    where
+        T: num::Integer + CheckedNeg + CheckedSub + Copy,
+    {
+        let axial = match self {
+            Orientation::Flat => flat::coordinate::try_convert_offset_to_axial(offset)?,
+            Orientation::Pointy => pointy::coordinate::try_convert_offset_to_axial(offset)?,
+        };
+        return flat::coordinate::try_convert_axial_to_cube(axial);
+    }
+
+    pub fn try_convert_cube_to_offset<T>(&self, cube: Cube<T>) -> Result<Offset<T>, OverflowError>
+    where
+        T: num::Integer + CheckedAdd + Copy,
+    {
+        let axial = flat::coordinate::convert_cube_to_axial(cube);
+        return match self {
+            Orientation::Flat => flat::coordinate::try_convert_axial_to_offset(axial),
+            Orientation::Pointy => pointy::coordinate::try_convert_axial_to_offset(axial),
+        };
+    }
+
+    pub fn convert_offset_to_point<I, F>(&self, size: (F, F), offset: Offset<I>) -> (F, F)
+    where
+        I: num::Integer + num::Signed + num::cast::AsPrimitive<F>,
+        F: 'static + num::Float,
+    {
+        return match self {
+            Orientation::Flat => flat::coordinate::convert_offset_to_point(size, offset),
+            Orientation::Pointy => pointy::coordinate::convert_offset_to_point(size, offset),
+        };
+    }
+
+    pub fn convert_point_to_offset<I, F>(&self, size: (F, F), point: (F, F)) -> Offset<I>
+    where
+        I: 'static + num::Integer + num::Signed + Copy,
+        F: num::Float + num::cast::AsPrimitive<I>,
+    {
+        return match self {
+            Orientation::Flat => flat::coordinate::convert_point_to_offset(size, point),
+            Orientation::Pointy => pointy::coordinate::convert_point_to_offset(size, point),
+        };
+    }
+
+    // the point is computed from the cube in F, so only the cube has to fit in T
+    pub fn try_convert_offset_to_point<T, F>(
+        &self,
+        size: (F, F),
+        offset: Offset<T>,
+    ) -> Result<(F, F), OverflowError>
+    where
+        T: num::Integer + CheckedNeg + CheckedSub + AsPrimitive<F>,
+        F: 'static + num::Float + AsPrimitive<F>,
+    {
+        let cube = self.try_convert_offset_to_cube(offset)?;
+        let cube = Cube {
+            q: cube.q.as_(),
+            r: cube.r.as_(),
+            s: cube.s.as_(),
+        };
+        return Ok(self.convert_cube_to_point(size, cube));
+    }
+
+    // points that aren't finite or are too far out, and a size of 0, are an error as well
+    pub fn try_convert_point_to_offset<I, F>(
+        &self,
+        size: (F, F),
+        point: (F, F),
+    ) -> Result<Offset<I>, OverflowError>
+    where
+        I: 'static + num::Integer + num::Signed + num::Bounded + Copy + AsPrimitive<F>,
+        F: num::Float + AsPrimitive<I>,
+    {
+        // axial q and r stay within 1.5 times the point over the size and s within 2.5 times,
+        // so a quarter of the maximum leaves room for rounding and the offset conversion
+        let four = F::one() + F::one() + F::one() + F::one();
+        let limit = I::max_value().as_() / four;
+        let within = |value: F, size: F| (value / size).abs() < limit;
+        if !within(point.0, size.0) || !within(point.1, size.1) {
+            return Err(OverflowError);
+        }
+        return Ok(self.convert_point_to_offset(size, point));
+    }
+
+    pub fn convert_cube_to_point<I, F>(&self, size: (F, F), cube: Cube<I>) -> (F, F)
+    where
+        I: num::Num + num::cast::AsPrimitive<F>,
+        F: 'static + num::Float,
+    {
+        let axial = flat::coordinate::convert_cube_to_axial(cube);
+        return match self {
+            Orientation::Flat => flat::coordinate::convert_axial_to_point(size, axial),
+            Orientation::Pointy => pointy::coordinate::convert_axial_to_point(size, axial),
+        };
+    }
+
+    // the neighbor in direction i of get_cube_direction_vectors is at index i
+    pub fn get_neighbor_offsets<T>(&self, offset: Offset<T>) -> [Offset<T>; 6]
+    where
+        T: Number + num::Integer,
+    {
+        let cube = self.convert_offset_to_cube(offset);
+        return get_cube_direction_vectors()
+            .map(|direction| self.convert_cube_to_offset(cube + direction));
+    }
+}
+
+// the offset conversions only take signed types, these must not compile
+/// ```compile_fail
+/// hex::flat::coordinate::convert_offset_to_axial(hex::Offset { q: 1u32, r: 0 });
+/// ```
+/// ```compile_fail
+/// hex::pointy::coordinate::convert_offset_to_axial(hex::Offset { q: 1u32, r: 0 });
+/// ```
+/// ```compile_fail
+/// hex::Orientation::Flat.convert_offset_to_cube(hex::Offset { q: 1u32, r: 0 });
+/// ```
+#[cfg(doctest)]
+struct UnsignedOffsets;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dispatch() {
+        let offset = Offset { q: 1, r: 2 };
+        for orientation in [Orientation::Flat, Orientation::Pointy] {
+            let cube = orientation.convert_offset_to_cube(offset);
+            assert_eq!(orientation.convert_cube_to_offset(cube), offset);
+            assert_eq!(orientation.try_convert_offset_to_cube(offset), Ok(cube));
+            assert_eq!(orientation.try_convert_cube_to_offset(cube), Ok(offset));
+            let extreme = Offset {
+                q: i32::MIN,
+                r: i32::MIN,
+            };
+            assert_eq!(
+                orientation.try_convert_offset_to_cube(extreme),
+                Err(OverflowError)
+            );
+            assert_eq!(
+                orientation.try_convert_offset_to_point((42.0, 30.0), extreme),
+                Err(OverflowError)
+            );
+            let point = orientation.convert_offset_to_point((42.0, 30.0), offset);
+            assert_eq!(orientation.convert_cube_to_point((42.0, 30.0), cube), point);
+            assert_eq!(
+                orientation.try_convert_offset_to_point((42.0, 30.0), offset),
+                Ok(point)
+            );
+            assert_eq!(
+                orientation.try_convert_point_to_offset((42.0, 30.0), point),
+                Ok(offset)
+            );
+            for point in [(f64::NAN, 0.0), (0.0, f64::INFINITY), (42.0 * 6e8, 0.0)] {
+                let result = orientation.try_convert_point_to_offset::<i32, _>((42.0, 30.0), point);
+                assert_eq!(result, Err(OverflowError));
+            }
+            let result = orientation.try_convert_point_to_offset::<i32, _>((0.0, 30.0), point);
+            assert_eq!(result, Err(OverflowError));
+            assert_eq!(
+                orientation.convert_point_to_offset((42.0, 30.0), point),
+                offset
+            );
+            for neighbor in orientation.get_neighbor_offsets(offset) {
+                let neighbor = orientation.convert_offset_to_cube(neighbor);
+                assert_eq!(calculate_distance(cube, neighbor), 1);
+            }
+        }
+        assert_eq!(
+            Orientation::Flat.convert_offset_to_point((42.0, 30.0), offset),
+            flat::coordinate::convert_offset_to_point((42.0, 30.0), offset)
+        );
+        assert_eq!(
+            Orientation::Pointy.convert_offset_to_point((42.0, 30.0), offset),
+            pointy::coordinate::convert_offset_to_point((42.0, 30.0), offset)
+        );
+    }
+}
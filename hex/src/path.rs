@@ -0,0 +1,562 @@
+// pathfinding over a width x height offset map, where cost is paid when entering a tile
+// None from cost marks an obstacle, and every cost should be at least 1 for the heuristic to hold
+// https://www.redblobgames.com/pathfinding/a-star/introduction.html
+// https://webdocs.cs.ualberta.ca/~mmueller/ps/hpastar.pdf
+
+use crate::{shared::index::*, shared::neighbor::*, structs::*};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+struct Search {
+    distances: HashMap<usize, u32>,
+    parents: HashMap<usize, usize>,
+}
+
+// a* over tile indices, without a goal every allowed tile is visited like dijkstra
+fn search<C, A>(
+    orientation: Orientation,
+    size: (usize, usize),
+    cost: C,
+    start: usize,
+    goal: Option<usize>,
+    is_allowed: A,
+) -> Search
+where
+    C: Fn(usize) -> Option<u32>,
+    A: Fn(usize) -> bool,
+{
+    let to_cube = |index| {
+        orientation.convert_offset_to_cube(convert_index_to_offset::<i32>(size, index).unwrap())
+    };
+    let heuristic = |index| match goal {
+        Some(goal) => calculate_distance(to_cube(index), to_cube(goal)) as u32,
+        None => 0,
+    };
+    let mut distances = HashMap::from([(start, 0u32)]);
+    let mut parents = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+    while let Some(Reverse((_, distance, index))) = heap.pop() {
+        if distances[&index] < distance {
+            continue;
+        }
+        if Some(index) == goal {
+            break;
+        }
+        let offset = convert_index_to_offset::<i32>(size, index).unwrap();
+        for neighbor in orientation.get_neighbor_offsets(offset) {
+            let Some(next) = convert_offset_to_index(size, neighbor) else {
+                continue;
+            };
+            if !is_allowed(next) {
+                continue;
+            }
+            let Some(cost) = cost(next) else {
+                continue;
+            };
+            let distance = distance.saturating_add(cost);
+            if distances.get(&next).is_some_and(|best| *best <= distance) {
+                continue;
+            }
+            distances.insert(next, distance);
+            parents.insert(next, index);
+            heap.push(Reverse((
+                distance.saturating_add(heuristic(next)),
+                distance,
+                next,
+            )));
+        }
+    }
+    return Search { distances, parents };
+}
+
+// from start to goal, both included
+fn get_path(parents: &HashMap<usize, usize>, start: usize, goal: usize) -> Vec<usize> {
+    let mut path = vec![goal];
+    let mut index = goal;
+    while index != start {
+        index = parents[&index];
+        path.push(index);
+    }
+    path.reverse();
+    return path;
+}
+
+fn convert_path(size: (usize, usize), path: Vec<usize>) -> Vec<Offset<i32>> {
+    return path
+        .into_iter()
+        .map(|index| convert_index_to_offset(size, index).unwrap())
+        .collect();
+}
+
+// the path from start to goal, both included, and its total cost
+pub fn find_path<C>(
+    orientation: Orientation,
+    size: (usize, usize),
+    start: Offset<i32>,
+    goal: Offset<i32>,
+    cost: C,
+) -> Option<(Vec<Offset<i32>>, u32)>
+where
+    C: Fn(Offset<i32>) -> Option<u32>,
+{
+    let start = convert_offset_to_index(size, start)?;
+    let goal = convert_offset_to_index(size, goal)?;
+    let cost = |index| cost(convert_index_to_offset(size, index).unwrap());
+    let result = search(orientation, size, cost, start, Some(goal), |_| true);
+    let distance = *result.distances.get(&goal)?;
+    let path = get_path(&result.parents, start, goal);
+    return Some((convert_path(size, path), distance));
+}
+
+// splits the map into cluster_size x cluster_size clusters of offsets
+// every contiguous run of passable tiles along a cluster border gets one entrance in its middle
+// costs between entrances of the same cluster are precomputed, so queries only search the abstract graph
+// and refine the few clusters on the way, paths are close to but not always the cheapest
+pub struct HierarchicalPathfinder {
+    orientation: Orientation,
+    size: (usize, usize),
+    cluster_size: usize,
+    clusters: (usize, usize),
+    costs: Vec<Option<u32>>,
+    // keyed by (lower cluster, higher cluster), holding (tile in lower, tile in higher)
+    entrances: HashMap<(usize, usize), Vec<(usize, usize)>>,
+    // per cluster, the cost from each entrance tile to the other entrance tiles of the cluster
+    edges: Vec<HashMap<usize, Vec<(usize, u32)>>>,
+}
+
+impl HierarchicalPathfinder {
+    pub fn new<C>(
+        orientation: Orientation,
+        size: (usize, usize),
+        cluster_size: usize,
+        cost: C,
+    ) -> Self
+    where
+        C: Fn(Offset<i32>) -> Option<u32>,
+    {
+        let cluster_size = cluster_size.max(1);
+        let clusters = (size.0.div_ceil(cluster_size), size.1.div_ceil(cluster_size));
+        let costs = (0..size.0 * size.1)
+            .map(|index| cost(convert_index_to_offset(size, index).unwrap()))
+            .collect();
+        let mut pathfinder = HierarchicalPathfinder {
+            orientation,
+            size,
+            cluster_size,
+            clusters,
+            costs,
+            entrances: HashMap::new(),
+            edges: vec![HashMap::new(); clusters.0 * clusters.1],
+        };
+        for cluster in 0..clusters.0 * clusters.1 {
+            for neighbor in pathfinder.get_neighbor_clusters(cluster) {
+                if cluster < neighbor {
+                    pathfinder.update_entrances(cluster, neighbor);
+                }
+            }
+        }
+        for cluster in 0..clusters.0 * clusters.1 {
+            pathfinder.update_edges(cluster);
+        }
+        return pathfinder;
+    }
+
+    pub fn get_cost(&self, offset: Offset<i32>) -> Option<u32> {
+        return convert_offset_to_index(self.size, offset).and_then(|index| self.costs[index]);
+    }
+
+    // only the cluster of the tile and its neighbors are updated
+    pub fn set_cost(&mut self, offset: Offset<i32>, cost: Option<u32>) {
+        let Some(index) = convert_offset_to_index(self.size, offset) else {
+            return;
+        };
+        self.costs[index] = cost;
+        let cluster = self.get_cluster(index);
+        let neighbors = self.get_neighbor_clusters(cluster);
+        for neighbor in &neighbors {
+            self.update_entrances(cluster, *neighbor);
+        }
+        self.update_edges(cluster);
+        for neighbor in neighbors {
+            self.update_edges(neighbor);
+        }
+    }
+
+    fn get_cluster(&self, index: usize) -> usize {
+        let q = index % self.size.0 / self.cluster_size;
+        let r = index / self.size.0 / self.cluster_size;
+        return r * self.clusters.0 + q;
+    }
+
+    // offsets shift by at most one column or row, so neighbors of a tile are in the 8 clusters around
+    fn get_neighbor_clusters(&self, cluster: usize) -> Vec<usize> {
+        let q = (cluster % self.clusters.0) as isize;
+        let r = (cluster / self.clusters.0) as isize;
+        let mut neighbors = Vec::new();
+        for dr in -1..=1 {
+            for dq in -1..=1 {
+                let (q, r) = (q + dq, r + dr);
+                if (dq, dr) == (0, 0) || q < 0 || r < 0 {
+                    continue;
+                }
+                let (q, r) = (q as usize, r as usize);
+                if q < self.clusters.0 && r < self.clusters.1 {
+                    neighbors.push(r * self.clusters.0 + q);
+                }
+            }
+        }
+        return neighbors;
+    }
+
+    fn get_cluster_tiles(&self, cluster: usize) -> impl Iterator<Item = usize> + '_ {
+        let q = cluster % self.clusters.0 * self.cluster_size;
+        let r = cluster / self.clusters.0 * self.cluster_size;
+        let width = self.size.0;
+        let end_q = (q + self.cluster_size).min(self.size.0);
+        let end_r = (r + self.cluster_size).min(self.size.1);
+        (r..end_r).flat_map(move |r| (q..end_q).map(move |q| r * width + q))
+    }
+
+    fn get_neighbor_tiles(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let offset = convert_index_to_offset::<i32>(self.size, index).unwrap();
+        self.orientation
+            .get_neighbor_offsets(offset)
+            .into_iter()
+            .filter_map(|neighbor| convert_offset_to_index(self.size, neighbor))
+    }
+
+    fn update_entrances(&mut self, cluster: usize, neighbor: usize) {
+        let mut transitions = Vec::new();
+        for tile in self.get_cluster_tiles(cluster) {
+            if self.costs[tile].is_none() {
+                continue;
+            }
+            for other in self.get_neighbor_tiles(tile) {
+                if self.get_cluster(other) == neighbor && self.costs[other].is_some() {
+                    transitions.push((tile, other));
+                }
+            }
+        }
+
+        // transitions are in the same run when both sides are the same or adjacent tiles
+        let is_close =
+            |a: usize, b: usize| a == b || self.get_neighbor_tiles(a).any(|tile| tile == b);
+        let mut visited = HashSet::new();
+        let mut entrances = Vec::new();
+        for transition in &transitions {
+            if !visited.insert(*transition) {
+                continue;
+            }
+            let mut run = vec![*transition];
+            let mut stack = vec![*transition];
+            while let Some((a, b)) = stack.pop() {
+                for other in &transitions {
+                    if !visited.contains(other) && is_close(a, other.0) && is_close(b, other.1) {
+                        visited.insert(*other);
+                        run.push(*other);
+                        stack.push(*other);
+                    }
+                }
+            }
+            run.sort();
+            let (a, b) = run[run.len() / 2];
+            entrances.push(if cluster < neighbor { (a, b) } else { (b, a) });
+        }
+        entrances.sort();
+        let key = (cluster.min(neighbor), cluster.max(neighbor));
+        self.entrances.insert(key, entrances);
+    }
+
+    fn get_entrance_tiles(&self, cluster: usize) -> Vec<usize> {
+        let mut tiles = Vec::new();
+        for neighbor in self.get_neighbor_clusters(cluster) {
+            let key = (cluster.min(neighbor), cluster.max(neighbor));
+            for (a, b) in self.entrances.get(&key).into_iter().flatten() {
+                tiles.push(if cluster < neighbor { *a } else { *b });
+            }
+        }
+        tiles.sort();
+        tiles.dedup();
+        return tiles;
+    }
+
+    fn search_cluster(&self, cluster: usize, start: usize, goal: Option<usize>) -> Search {
+        let cost = |index: usize| self.costs[index];
+        let is_allowed = |index| self.get_cluster(index) == cluster;
+        return search(self.orientation, self.size, cost, start, goal, is_allowed);
+    }
+
+    fn update_edges(&mut self, cluster: usize) {
+        let tiles = self.get_entrance_tiles(cluster);
+        let mut edges = HashMap::new();
+        for tile in &tiles {
+            let result = self.search_cluster(cluster, *tile, None);
+            let reachable = tiles
+                .iter()
+                .filter(|other| *other != tile)
+                .filter_map(|other| Some((*other, *result.distances.get(other)?)))
+                .collect();
+            edges.insert(*tile, reachable);
+        }
+        self.edges[cluster] = edges;
+    }
+
+    // entrances in other clusters next to the given entrance tile
+    fn get_transitions(&self, tile: usize) -> Vec<usize> {
+        let cluster = self.get_cluster(tile);
+        let mut transitions = Vec::new();
+        for neighbor in self.get_neighbor_clusters(cluster) {
+            let key = (cluster.min(neighbor), cluster.max(neighbor));
+            for (a, b) in self.entrances.get(&key).into_iter().flatten() {
+                if *a == tile {
+                    transitions.push(*b);
+                }
+                if *b == tile {
+                    transitions.push(*a);
+                }
+            }
+        }
+        return transitions;
+    }
+
+    // the path from start to goal, both included, and its total cost
+    pub fn find_path(
+        &self,
+        start: Offset<i32>,
+        goal: Offset<i32>,
+    ) -> Option<(Vec<Offset<i32>>, u32)> {
+        let start = convert_offset_to_index(self.size, start)?;
+        let goal = convert_offset_to_index(self.size, goal)?;
+        self.costs[goal]?;
+        let start_cluster = self.get_cluster(start);
+        let goal_cluster = self.get_cluster(goal);
+
+        let mut best = None;
+        if start_cluster == goal_cluster {
+            let result = self.search_cluster(start_cluster, start, Some(goal));
+            if let Some(distance) = result.distances.get(&goal) {
+                best = Some((get_path(&result.parents, start, goal), *distance));
+            }
+        }
+
+        // connect start and goal to the entrances of their clusters
+        let result = self.search_cluster(start_cluster, start, None);
+        let start_edges: Vec<(usize, u32)> = self.edges[start_cluster]
+            .keys()
+            .filter_map(|tile| Some((*tile, *result.distances.get(tile)?)))
+            .collect();
+        let goal_edges: HashMap<usize, u32> = self.edges[goal_cluster]
+            .keys()
+            .filter_map(|tile| {
+                let result = self.search_cluster(goal_cluster, *tile, Some(goal));
+                Some((*tile, *result.distances.get(&goal)?))
+            })
+            .collect();
+
+        let to_cube = |index| {
+            self.orientation
+                .convert_offset_to_cube(convert_index_to_offset::<i32>(self.size, index).unwrap())
+        };
+        let heuristic = |index| calculate_distance(to_cube(index), to_cube(goal)) as u32;
+        let mut distances = HashMap::from([(start, 0u32)]);
+        let mut parents = HashMap::new();
+        let mut heap = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+        while let Some(Reverse((_, distance, tile))) = heap.pop() {
+            if distances[&tile] < distance
+                || best.as_ref().is_some_and(|(_, best)| *best <= distance)
+            {
+                continue;
+            }
+            if tile == goal {
+                break;
+            }
+            let cluster = self.get_cluster(tile);
+            let mut edges = match tile == start {
+                true => start_edges.clone(),
+                false => self.edges[cluster].get(&tile).cloned().unwrap_or_default(),
+            };
+            edges.extend(
+                self.get_transitions(tile)
+                    .into_iter()
+                    .filter_map(|other| Some((other, self.costs[other]?))),
+            );
+            edges.extend(goal_edges.get(&tile).map(|cost| (goal, *cost)));
+            for (next, cost) in edges {
+                let distance = distance.saturating_add(cost);
+                if distances.get(&next).is_some_and(|best| *best <= distance) {
+                    continue;
+                }
+                distances.insert(next, distance);
+                parents.insert(next, tile);
+                heap.push(Reverse((
+                    distance.saturating_add(heuristic(next)),
+                    distance,
+                    next,
+                )));
+            }
+        }
+
+        if let Some(distance) = distances.get(&goal) {
+            if best.as_ref().is_none_or(|(_, best)| distance < best) {
+                best = Some((self.refine(get_path(&parents, start, goal)), *distance));
+            }
+        }
+        return best.map(|(path, distance)| (convert_path(self.size, path), distance));
+    }
+
+    // replaces every step within a cluster by the tiles in between
+    fn refine(&self, abstract_path: Vec<usize>) -> Vec<usize> {
+        let mut path = vec![abstract_path[0]];
+        for step in abstract_path.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            let cluster = self.get_cluster(from);
+            if cluster != self.get_cluster(to) {
+                path.push(to);
+                continue;
+            }
+            let result = self.search_cluster(cluster, from, Some(to));
+            path.extend(get_path(&result.parents, from, to).into_iter().skip(1));
+        }
+        return path;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SIZE: (usize, usize) = (24, 20);
+    const ORIENTATIONS: [Orientation; 2] = [Orientation::Flat, Orientation::Pointy];
+
+    // walls with gaps, plus some expensive tiles
+    fn cost(offset: Offset<i32>) -> Option<u32> {
+        if offset.q == 7 && offset.r % 6 != 2 {
+            return None;
+        }
+        if offset.r == 11 && offset.q % 9 != 4 {
+            return None;
+        }
+        if (offset.q * 7 + offset.r * 13) % 5 == 0 {
+            return Some(3);
+        }
+        return Some(1);
+    }
+
+    fn assert_path(
+        orientation: Orientation,
+        cost: impl Fn(Offset<i32>) -> Option<u32>,
+        path: &[Offset<i32>],
+        distance: u32,
+    ) {
+        let mut total = 0;
+        for step in path.windows(2) {
+            let a = orientation.convert_offset_to_cube(step[0]);
+            let b = orientation.convert_offset_to_cube(step[1]);
+            assert_eq!(calculate_distance(a, b), 1, "{:?}", path);
+            total += cost(step[1]).unwrap();
+        }
+        assert_eq!(total, distance, "{:?}", path);
+    }
+
+    fn get_queries() -> Vec<(Offset<i32>, Offset<i32>)> {
+        return vec![
+            (Offset { q: 0, r: 0 }, Offset { q: 23, r: 19 }),
+            (Offset { q: 2, r: 18 }, Offset { q: 20, r: 1 }),
+            (Offset { q: 5, r: 5 }, Offset { q: 6, r: 3 }),
+            (Offset { q: 10, r: 10 }, Offset { q: 10, r: 12 }),
+            (Offset { q: 0, r: 0 }, Offset { q: 7, r: 0 }),
+        ];
+    }
+
+    #[test]
+    fn a_star() {
+        for orientation in ORIENTATIONS {
+            let (path, distance) = find_path(
+                orientation,
+                SIZE,
+                Offset { q: 0, r: 0 },
+                Offset { q: 12, r: 0 },
+                cost,
+            )
+            .unwrap();
+            assert_eq!(path[0], Offset { q: 0, r: 0 });
+            assert_eq!(*path.last().unwrap(), Offset { q: 12, r: 0 });
+            assert_path(orientation, cost, &path, distance);
+
+            let field = crate::field::calculate_weighted_distance_field(
+                orientation,
+                SIZE,
+                &[Offset { q: 0, r: 0 }],
+                cost,
+            );
+            assert_eq!(field[12], Some(distance));
+            assert_eq!(
+                find_path(
+                    orientation,
+                    SIZE,
+                    Offset { q: 0, r: 0 },
+                    Offset { q: 7, r: 0 },
+                    cost
+                ),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn hierarchical() {
+        for orientation in ORIENTATIONS {
+            let pathfinder = HierarchicalPathfinder::new(orientation, SIZE, 5, cost);
+            for (start, goal) in get_queries() {
+                let exp = find_path(orientation, SIZE, start, goal, cost);
+                let result = pathfinder.find_path(start, goal);
+                assert_eq!(result.is_some(), exp.is_some(), "{:?} {:?}", start, goal);
+                let (Some((path, distance)), Some((_, exp))) = (result, exp) else {
+                    continue;
+                };
+                assert_eq!(path[0], start);
+                assert_eq!(*path.last().unwrap(), goal);
+                assert_path(orientation, cost, &path, distance);
+                assert!(
+                    distance >= exp && distance * 2 <= exp * 3,
+                    "{} {}",
+                    distance,
+                    exp
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        for orientation in ORIENTATIONS {
+            let mut pathfinder = HierarchicalPathfinder::new(orientation, SIZE, 5, cost);
+            let changes = [
+                (Offset { q: 7, r: 8 }, Some(1)),
+                (Offset { q: 4, r: 11 }, None),
+                (Offset { q: 13, r: 11 }, None),
+                (Offset { q: 15, r: 4 }, Some(9)),
+                (Offset { q: 9, r: 14 }, None),
+            ];
+            for (offset, cost) in changes {
+                pathfinder.set_cost(offset, cost);
+            }
+            let changed =
+                |offset: Offset<i32>| match changes.iter().find(|(other, _)| *other == offset) {
+                    Some((_, cost)) => *cost,
+                    None => cost(offset),
+                };
+            let rebuilt = HierarchicalPathfinder::new(orientation, SIZE, 5, changed);
+            assert_eq!(pathfinder.entrances, rebuilt.entrances);
+            assert_eq!(pathfinder.edges, rebuilt.edges);
+            for (start, goal) in get_queries() {
+                assert_eq!(
+                    pathfinder.find_path(start, goal),
+                    rebuilt.find_path(start, goal)
+                );
+                let exp = find_path(orientation, SIZE, start, goal, changed);
+                assert_eq!(pathfinder.find_path(start, goal).is_some(), exp.is_some());
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::structs::*;
+use num::Integer;
+
+pub use crate::shared::batch::*;
+
+// coordinate::convert_point_to_offset for every point, specialized to f32 and i32
+// points must be finite and within 2^29 tiles of the origin, further out the i32 arithmetic overflows
+// f32 can't tell tiles apart beyond 2^23 anyway
+pub fn convert_points_to_offsets(
+    size: (f32, f32),
+    points: &[(f32, f32)],
+    offsets: &mut [Offset<i32>],
+) {
+    assert_eq!(points.len(), offsets.len());
+    for (point, offset) in points.iter().zip(offsets.iter_mut()) {
+        let q = point.0 / size.0;
+        let r = point.1 / size.1;
+        let q = q - r / 2.0; // every q contributes half y
+        let axial = round_axial_select(q, r);
+        let q = axial.q + Integer::div_floor(&axial.r, &2);
+        let r = axial.r;
+        *offset = Offset { q, r };
+    }
+}
+
+// coordinate::convert_offset_to_point for every offset, specialized to f32 and i32
+pub fn convert_offsets_to_points(
+    size: (f32, f32),
+    offsets: &[Offset<i32>],
+    points: &mut [(f32, f32)],
+) {
+    assert_eq!(offsets.len(), points.len());
+    for (offset, point) in offsets.iter().zip(points.iter_mut()) {
+        let q = offset.q - Integer::div_floor(&offset.r, &2);
+        let r = offset.r;
+        let q = q * 2 + r;
+        *point = (q as f32 / 2.0 * size.0, r as f32 * size.1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pointy::coordinate::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    const TILE_SIZE: (f32, f32) = (42.0, 30.0);
+
+    #[test]
+    fn points() {
+        let points: Vec<(f32, f32)> = (-60..60)
+            .flat_map(|i| (-60..60).map(move |j| (i as f32 * 3.7, j as f32 * 2.9)))
+            .collect();
+        let mut offsets = vec![Offset { q: 0, r: 0 }; points.len()];
+        convert_points_to_offsets(TILE_SIZE, &points, &mut offsets);
+        for (point, offset) in points.iter().zip(&offsets) {
+            assert_eq!(
+                convert_point_to_offset::<i32, f32>(TILE_SIZE, *point),
+                *offset,
+                "{:?}",
+                point
+            );
+        }
+
+        let mut result = vec![(0.0, 0.0); offsets.len()];
+        convert_offsets_to_points(TILE_SIZE, &offsets, &mut result);
+        for (offset, point) in offsets.iter().zip(result) {
+            assert_eq!(
+                convert_offset_to_point(TILE_SIZE, *offset),
+                point,
+                "{:?}",
+                offset
+            );
+        }
+    }
+}
@@ -1,13 +1,14 @@
 // this is odd-r with y inversed
 
-use crate::{shared::round, structs::*};
-use std::ops::{BitAnd, Neg};
+use crate::{error::*, shared::neighbor::*, shared::round, structs::*};
+use core::ops::Neg;
+use num::{CheckedAdd, CheckedSub};
 
 pub use crate::shared::coordinate::*;
 
 pub fn convert_point_to_offset<I, F>(size: (F, F), point: (F, F)) -> Offset<I>
 where
-    I: 'static + num::Num + BitAnd<Output = I> + Neg<Output = I> + Copy,
+    I: 'static + num::Integer + num::Signed + Copy,
     F: num::Float + num::cast::AsPrimitive<I>,
 {
     return convert_axial_to_offset(convert_point_to_axial(size, point));
@@ -15,7 +16,7 @@ where
 
 pub fn convert_offset_to_point<I, F>(size: (F, F), offset: Offset<I>) -> (F, F)
 where
-    I: num::Num + BitAnd<Output = I> + num::cast::AsPrimitive<F>,
+    I: num::Integer + num::Signed + num::cast::AsPrimitive<F>,
     F: 'static + num::Float,
 {
     return convert_axial_to_point(size, convert_offset_to_axial(offset));
@@ -26,7 +27,7 @@ where
 // https://www.redblobgames.com/grids/hexagons/#hex-to-pixel
 pub fn convert_point_to_axial<I, F>(size: (F, F), point: (F, F)) -> Axial<I>
 where
-    I: 'static + num::Num + BitAnd<Output = I> + Neg<Output = I> + Copy,
+    I: 'static + num::Integer + Neg<Output = I> + Copy,
     F: num::Float + num::cast::AsPrimitive<I>,
 {
     let one: F = num::one();
@@ -52,30 +53,102 @@ where
     return (q, r);
 }
 
+// corner i sits between the neighbors in direction i and i + 1 of get_cube_direction_vectors
+// https://www.redblobgames.com/grids/hexagons/#hex-to-pixel
+pub fn convert_axial_to_corners<I, F>(size: (F, F), axial: Axial<I>) -> [(F, F); 6]
+where
+    I: num::Num + num::cast::AsPrimitive<F>,
+    F: 'static + num::Float,
+    i32: num::cast::AsPrimitive<F>,
+{
+    let center = convert_axial_to_point(size, axial);
+    let directions = get_cube_direction_vectors::<i32>();
+    let three = F::one() + F::one() + F::one();
+    return core::array::from_fn(|i| {
+        let corner = directions[i] + directions[(i + 1) % 6];
+        let corner = convert_axial_to_point(size, convert_cube_to_axial(corner));
+        (center.0 + corner.0 / three, center.1 + corner.1 / three)
+    });
+}
+
 // https://www.redblobgames.com/grids/hexagons/#conversions-offset
+// floor division, so odd r below 0 shift the same way as odd r above 0
 pub fn convert_axial_to_offset<T>(axial: Axial<T>) -> Offset<T>
 where
-    T: num::Num + BitAnd<Output = T> + Copy,
+    T: num::Integer + num::Signed + Copy,
 {
-    let one = num::one();
-    let q = axial.q + (axial.r - (axial.r & one)) / (one + one);
+    let two = T::one() + T::one();
+    let q = axial.q + axial.r.div_floor(&two);
     let r = axial.r;
     return Offset { q, r };
 }
 
+// signed only, unsigned offsets go through try_convert_unsigned_offset_to_axial
 pub fn convert_offset_to_axial<T>(offset: Offset<T>) -> Axial<T>
 where
-    T: num::Num + BitAnd<Output = T> + Copy,
+    T: num::Integer + num::Signed + Copy,
 {
-    let one = num::one();
-    let q = offset.q - (offset.r - (offset.r & one)) / (one + one);
+    let two = T::one() + T::one();
+    let q = offset.q - offset.r.div_floor(&two);
     let r = offset.r;
     return Axial { q, r };
 }
 
+// floor(r / 2) is closer to 0 than r, so only the final addition can overflow
+pub fn try_convert_axial_to_offset<T>(axial: Axial<T>) -> Result<Offset<T>, OverflowError>
+where
+    T: num::Integer + CheckedAdd + Copy,
+{
+    let two = T::one() + T::one();
+    let half = axial.r.div_floor(&two);
+    let r = axial.r;
+    let q = axial.q.checked_add(&half).ok_or(OverflowError)?;
+    return Ok(Offset { q, r });
+}
+
+pub fn try_convert_offset_to_axial<T>(offset: Offset<T>) -> Result<Axial<T>, OverflowError>
+where
+    T: num::Integer + CheckedSub + Copy,
+{
+    let two = T::one() + T::one();
+    let half = offset.r.div_floor(&two);
+    let r = offset.r;
+    let q = offset.q.checked_sub(&half).ok_or(OverflowError)?;
+    return Ok(Axial { q, r });
+}
+
+// bounded maps can store offsets unsigned, the axial side stays signed since axial coordinates go below 0
+pub fn try_convert_unsigned_offset_to_axial<U, I>(
+    offset: Offset<U>,
+) -> Result<Axial<I>, OverflowError>
+where
+    U: num::Unsigned + num::ToPrimitive + Copy,
+    I: num::Integer + num::NumCast + CheckedSub + Copy,
+{
+    let q = <I as num::NumCast>::from(offset.q).ok_or(OverflowError)?;
+    let r = <I as num::NumCast>::from(offset.r).ok_or(OverflowError)?;
+    return try_convert_offset_to_axial(Offset { q, r });
+}
+
+// offsets below 0 don't fit in U, so they are an error as well
+pub fn try_convert_axial_to_unsigned_offset<I, U>(
+    axial: Axial<I>,
+) -> Result<Offset<U>, OverflowError>
+where
+    I: num::Integer + num::ToPrimitive + CheckedAdd + Copy,
+    U: num::Unsigned + num::NumCast + Copy,
+{
+    let offset = try_convert_axial_to_offset(axial)?;
+    let q = <U as num::NumCast>::from(offset.q).ok_or(OverflowError)?;
+    let r = <U as num::NumCast>::from(offset.r).ok_or(OverflowError)?;
+    return Ok(Offset { q, r });
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use alloc::vec;
+    use proptest::prelude::*;
     const TILE_SIZE: (f32, f32) = (42.0, 30.0);
 
     #[derive(Debug)]
@@ -84,7 +157,7 @@ mod test {
         exp: Offset<i32>,
     }
 
-    fn assert_cases(cases: &Vec<Case>) {
+    fn assert_cases(cases: &[Case]) {
         cases.iter().for_each(|case| {
             let offset = convert_point_to_offset(TILE_SIZE, case.translation);
             assert_eq!(
@@ -256,4 +329,128 @@ mod test {
 
         assert_cases(&cases);
     }
+
+    #[test]
+    fn corners() {
+        let axial = convert_offset_to_axial(Offset { q: 0, r: 0 });
+        let corners = convert_axial_to_corners(TILE_SIZE, axial);
+        let directions = get_cube_direction_vectors::<i32>();
+        for (i, direction) in directions.iter().enumerate() {
+            // corner i is also corner i + 2 of the neighbor in direction i
+            let neighbor = convert_cube_to_axial(*direction);
+            let shared = convert_axial_to_corners(TILE_SIZE, neighbor)[(i + 2) % 6];
+            let exp = corners[i];
+            assert!((shared.0 - exp.0).abs() < 0.001 && (shared.1 - exp.1).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn checked() {
+        let axial = Axial { q: 3, r: -2 };
+        let offset = try_convert_axial_to_offset(axial).unwrap();
+        assert_eq!(offset, convert_axial_to_offset(axial));
+        let axial = try_convert_offset_to_axial(offset).unwrap();
+        assert_eq!((axial.q, axial.r), (3, -2));
+
+        let axial = Axial { q: i16::MAX, r: 2 };
+        assert_eq!(try_convert_axial_to_offset(axial), Err(OverflowError));
+        let offset = Offset { q: i16::MIN, r: 2 };
+        assert_eq!(
+            try_convert_offset_to_axial(offset).err(),
+            Some(OverflowError)
+        );
+        let offset = Offset { q: i16::MIN, r: -2 };
+        assert!(try_convert_offset_to_axial(offset).is_ok());
+    }
+
+    fn get_neighbor_deltas(offset: (i32, i32)) -> [(i32, i32); 6] {
+        let offset = Offset {
+            q: offset.0,
+            r: offset.1,
+        };
+        let cube = convert_axial_to_cube(convert_offset_to_axial(offset));
+        return get_cube_direction_vectors().map(|direction| {
+            let neighbor = convert_axial_to_offset(convert_cube_to_axial(cube + direction));
+            return (neighbor.q - offset.q, neighbor.r - offset.r);
+        });
+    }
+
+    #[test]
+    fn negative_quadrants() {
+        // (axial q, axial r, offset q, offset r)
+        for (aq, ar, oq, or) in [
+            (0, -1, -1, -1),
+            (2, -3, 0, -3),
+            (-1, -2, -2, -2),
+            (-4, -5, -7, -5),
+        ] {
+            let offset = convert_axial_to_offset(Axial { q: aq, r: ar });
+            assert_eq!((offset.q, offset.r), (oq, or));
+            let axial = convert_offset_to_axial(offset);
+            assert_eq!((axial.q, axial.r), (aq, ar));
+        }
+
+        // the neighbor layout only depends on whether r is odd, on either side of 0
+        for q in -7..7 {
+            for r in -7..7 {
+                assert_eq!(
+                    get_neighbor_deltas((q, r)),
+                    get_neighbor_deltas((0, r.rem_euclid(2))),
+                    "offset: {:?}",
+                    (q, r)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unsigned() {
+        let (aq, ar, oq, or) = (-2, 5, 0, 5);
+        let axial: Axial<i32> = try_convert_unsigned_offset_to_axial(Offset {
+            q: oq as u32,
+            r: or as u32,
+        })
+        .unwrap();
+        assert_eq!((axial.q, axial.r), (aq, ar));
+        let offset: Offset<u32> = try_convert_axial_to_unsigned_offset(axial).unwrap();
+        assert_eq!((offset.q, offset.r), (oq as u32, or as u32));
+
+        let axial = Axial { q: -1, r: 0 };
+        assert_eq!(
+            try_convert_axial_to_unsigned_offset::<i32, u32>(axial),
+            Err(OverflowError)
+        );
+        let offset = Offset { q: u32::MAX, r: 0 };
+        assert_eq!(
+            try_convert_unsigned_offset_to_axial::<u32, i32>(offset).err(),
+            Some(OverflowError)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(q in -1_000_000..1_000_000, r in -1_000_000..1_000_000) {
+            let offset = Offset { q, r };
+            let cube = convert_axial_to_cube(convert_offset_to_axial(offset));
+            prop_assert!(cube.is_valid());
+            prop_assert_eq!(convert_axial_to_offset(convert_cube_to_axial(cube)), offset);
+            prop_assert_eq!(try_convert_offset_to_axial(offset), Ok(convert_offset_to_axial(offset)));
+        }
+
+        // within 0.3 of a tile from the center stays inside the hex for both orientations
+        #[test]
+        fn point_round_trip(
+            q in -100_000..100_000,
+            r in -100_000..100_000,
+            dx in -0.3..0.3,
+            dy in -0.3..0.3,
+        ) {
+            let size = (42.0, 30.0);
+            let offset = Offset { q, r };
+            let (x, y) = convert_offset_to_point(size, offset);
+            prop_assert_eq!(convert_point_to_offset::<i32, f64>(size, (x, y)), offset);
+            let point = (x + dx * size.0, y + dy * size.1);
+            prop_assert_eq!(convert_point_to_offset::<i32, f64>(size, point), offset);
+        }
+    }
 }
@@ -0,0 +1,52 @@
+use crate::{pointy::coordinate::*, shared::neighbor::*, structs::*};
+
+pub use crate::shared::edge::*;
+
+// the middle of the edge
+pub fn convert_edge_to_point<I, F>(size: (F, F), edge: HexEdge<I>) -> (F, F)
+where
+    I: Number + num::cast::AsPrimitive<F>,
+    F: 'static + num::Float,
+    i32: num::cast::AsPrimitive<F>,
+{
+    let [a, b] = get_edge_vertices(edge).map(|vertex| convert_vertex_to_point(size, vertex));
+    let two = F::one() + F::one();
+    return ((a.0 + b.0) / two, (a.1 + b.1) / two);
+}
+
+pub fn convert_vertex_to_point<I, F>(size: (F, F), vertex: HexVertex<I>) -> (F, F)
+where
+    I: Number + num::cast::AsPrimitive<F>,
+    F: 'static + num::Float,
+    i32: num::cast::AsPrimitive<F>,
+{
+    let axial = convert_cube_to_axial(vertex.cube());
+    return convert_axial_to_corners(size, axial)[vertex.corner()];
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TILE_SIZE: (f32, f32) = (42.0, 30.0);
+
+    #[test]
+    fn points() {
+        let cube = Cube::new(1, -2);
+        let center = convert_axial_to_point(TILE_SIZE, convert_cube_to_axial(cube));
+        let corners = convert_axial_to_corners(TILE_SIZE, convert_cube_to_axial(cube));
+        for (i, vertex) in get_cube_vertices(cube).into_iter().enumerate() {
+            let point = convert_vertex_to_point(TILE_SIZE, vertex);
+            assert!((point.0 - corners[i].0).abs() < 0.001);
+            assert!((point.1 - corners[i].1).abs() < 0.001);
+        }
+        for edge in get_cube_edges(cube) {
+            let [a, b] = get_edge_cubes(edge);
+            let other = if a == cube { b } else { a };
+            let other = convert_axial_to_point(TILE_SIZE, convert_cube_to_axial(other));
+            let point = convert_edge_to_point(TILE_SIZE, edge);
+            assert!((point.0 - (center.0 + other.0) / 2.0).abs() < 0.001);
+            assert!((point.1 - (center.1 + other.1) / 2.0).abs() < 0.001);
+        }
+    }
+}
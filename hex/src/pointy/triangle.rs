@@ -0,0 +1,68 @@
+use crate::{pointy::coordinate::*, shared::neighbor::*, structs::*};
+
+pub use crate::shared::triangle::*;
+
+// the centroid of the triangle
+pub fn convert_triangle_to_point<I, F>(size: (F, F), triangle: HexTriangle<I>) -> (F, F)
+where
+    I: Number + num::cast::AsPrimitive<F>,
+    F: 'static + num::Float,
+{
+    let three = F::one() + F::one() + F::one();
+    let point = convert_axial_to_point(size, convert_cube_to_axial(triangle.centroid()));
+    return (point.0 / three, point.1 / three);
+}
+
+// triangle i lies between the center, corner i - 1 and corner i
+pub fn convert_point_to_triangle<I, F>(size: (F, F), point: (F, F)) -> HexTriangle<I>
+where
+    I: 'static + Number + num::Integer + num::cast::AsPrimitive<F>,
+    F: num::Float + num::cast::AsPrimitive<I>,
+    i32: num::cast::AsPrimitive<F>,
+{
+    let axial = convert_point_to_axial(size, point);
+    let center = convert_axial_to_point(size, axial);
+    let corners = convert_axial_to_corners(size, axial);
+    let x = point.0 - center.0;
+    let y = point.1 - center.1;
+    // corners go clockwise, so the point is clockwise from corner i - 1 and counterclockwise from corner i
+    let index = (0..6)
+        .find(|i| {
+            let a = corners[(i + 5) % 6];
+            let b = corners[*i];
+            let a = (a.0 - center.0, a.1 - center.1);
+            let b = (b.0 - center.0, b.1 - center.1);
+            a.0 * y - a.1 * x <= F::zero() && x * b.1 - y * b.0 <= F::zero()
+        })
+        .unwrap_or(0);
+    return convert_cube_to_triangle(convert_axial_to_cube(axial), index);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TILE_SIZE: (f32, f32) = (42.0, 30.0);
+
+    #[test]
+    fn points() {
+        for cube in get_cubes_within_range(Cube::new(2, -1), 2) {
+            let axial = convert_cube_to_axial(cube);
+            let center = convert_axial_to_point(TILE_SIZE, axial);
+            let corners = convert_axial_to_corners(TILE_SIZE, axial);
+            for (i, triangle) in get_cube_triangles(cube).into_iter().enumerate() {
+                let centroid = convert_triangle_to_point(TILE_SIZE, triangle);
+                assert_eq!(convert_point_to_triangle(TILE_SIZE, centroid), triangle);
+
+                // close to each vertex of the triangle is still inside
+                for vertex in [center, corners[(i + 5) % 6], corners[i]] {
+                    let point = (
+                        centroid.0 + (vertex.0 - centroid.0) * 0.9,
+                        centroid.1 + (vertex.1 - centroid.1) * 0.9,
+                    );
+                    assert_eq!(convert_point_to_triangle(TILE_SIZE, point), triangle);
+                }
+            }
+        }
+    }
+}
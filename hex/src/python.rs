@@ -0,0 +1,446 @@
+// pyo3 bindings, built as the `hex` python module with maturin (see pyproject.toml)
+// offsets follow the same conventions as the rust side, flat is "even-q with y inversed", pointy is "odd-r with y inversed"
+
+// arithmetic is checked, results that don't fit in i32 raise OverflowError instead of panicking
+
+use crate::{
+    error::*, shared::coordinate, shared::index, shared::line, shared::neighbor, structs::*,
+};
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyOverflowError, PyValueError};
+use pyo3::prelude::*;
+
+#[pyclass(name = "Orientation", module = "hex", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyOrientation {
+    Flat,
+    Pointy,
+}
+
+impl From<PyOrientation> for Orientation {
+    fn from(orientation: PyOrientation) -> Self {
+        return match orientation {
+            PyOrientation::Flat => Orientation::Flat,
+            PyOrientation::Pointy => Orientation::Pointy,
+        };
+    }
+}
+
+#[pyclass(name = "Cube", module = "hex", eq, frozen, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyCube {
+    #[pyo3(get)]
+    pub q: i32,
+    #[pyo3(get)]
+    pub r: i32,
+    #[pyo3(get)]
+    pub s: i32,
+}
+
+#[pyclass(name = "Axial", module = "hex", eq, frozen, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyAxial {
+    #[pyo3(get)]
+    pub q: i32,
+    #[pyo3(get)]
+    pub r: i32,
+}
+
+#[pyclass(name = "Offset", module = "hex", eq, frozen, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyOffset {
+    #[pyo3(get)]
+    pub q: i32,
+    #[pyo3(get)]
+    pub r: i32,
+}
+
+// the orientation and tile size used for point conversions
+#[pyclass(name = "Layout", module = "hex", frozen)]
+#[derive(Debug, Clone, Copy)]
+pub struct PyLayout {
+    #[pyo3(get)]
+    pub orientation: PyOrientation,
+    #[pyo3(get)]
+    pub width: f64,
+    #[pyo3(get)]
+    pub height: f64,
+}
+
+impl From<OverflowError> for PyErr {
+    fn from(error: OverflowError) -> Self {
+        return PyOverflowError::new_err(error.to_string());
+    }
+}
+
+impl From<InvalidCube> for PyErr {
+    fn from(error: InvalidCube) -> Self {
+        return PyValueError::new_err(error.to_string());
+    }
+}
+
+impl From<PyCube> for Cube<i32> {
+    fn from(cube: PyCube) -> Self {
+        return Cube {
+            q: cube.q,
+            r: cube.r,
+            s: cube.s,
+        };
+    }
+}
+
+impl From<Cube<i32>> for PyCube {
+    fn from(cube: Cube<i32>) -> Self {
+        return PyCube {
+            q: cube.q,
+            r: cube.r,
+            s: cube.s,
+        };
+    }
+}
+
+impl From<PyOffset> for Offset<i32> {
+    fn from(offset: PyOffset) -> Self {
+        return Offset {
+            q: offset.q,
+            r: offset.r,
+        };
+    }
+}
+
+impl From<Offset<i32>> for PyOffset {
+    fn from(offset: Offset<i32>) -> Self {
+        return PyOffset {
+            q: offset.q,
+            r: offset.r,
+        };
+    }
+}
+
+#[pymethods]
+impl PyCube {
+    // s defaults to -q - r, an explicit s must keep q + r + s == 0
+    #[new]
+    #[pyo3(signature = (q, r, s = None))]
+    fn new(q: i32, r: i32, s: Option<i32>) -> PyResult<Self> {
+        let s = match s {
+            Some(s) => s,
+            None => coordinate::try_convert_axial_to_cube(Axial { q, r })?.s,
+        };
+        // q + r + s is summed in i64, so the check itself can't overflow
+        Cube::try_new(q as i64, r as i64, s as i64)?;
+        return Ok(PyCube { q, r, s });
+    }
+
+    fn __repr__(&self) -> String {
+        return format!("Cube{}", Cube::from(*self));
+    }
+
+    fn __add__(&self, other: PyCube) -> PyResult<PyCube> {
+        let cube = Cube::from(*self).checked_add(Cube::from(other));
+        return Ok(cube.ok_or(OverflowError)?.into());
+    }
+
+    fn __sub__(&self, other: PyCube) -> PyResult<PyCube> {
+        let cube = Cube::from(*self).checked_sub(Cube::from(other));
+        return Ok(cube.ok_or(OverflowError)?.into());
+    }
+
+    #[pyo3(name = "to_axial")]
+    fn convert_to_axial(&self) -> PyAxial {
+        let axial = coordinate::convert_cube_to_axial(Cube::from(*self));
+        return PyAxial {
+            q: axial.q,
+            r: axial.r,
+        };
+    }
+
+    fn distance(&self, other: PyCube) -> PyResult<i32> {
+        let distance = neighbor::calculate_checked_distance(Cube::from(*self), Cube::from(other));
+        return Ok(distance.ok_or(OverflowError)?);
+    }
+
+    fn neighbors(&self) -> PyResult<Vec<PyCube>> {
+        let cubes = neighbor::try_get_cubes_within_range(Cube::from(*self), 1)?;
+        return Ok(cubes
+            .filter(|cube| *cube != Cube::from(*self))
+            .map(PyCube::from)
+            .collect());
+    }
+
+    fn within_range(&self, n: i32) -> PyResult<Vec<PyCube>> {
+        let cubes = neighbor::try_get_cubes_within_range(Cube::from(*self), n)?;
+        return Ok(cubes.map(PyCube::from).collect());
+    }
+
+    fn line_to(&self, other: PyCube) -> PyResult<Vec<PyCube>> {
+        let (src, dst) = (Cube::from(*self), Cube::from(other));
+        // the line is interpolated in f64, only the distance has to fit
+        neighbor::calculate_checked_distance(src, dst).ok_or(OverflowError)?;
+        return Ok(line::get_line_cubes::<i32, f64>(src, dst)
+            .into_iter()
+            .map(PyCube::from)
+            .collect());
+    }
+}
+
+#[pymethods]
+impl PyAxial {
+    #[new]
+    fn new(q: i32, r: i32) -> Self {
+        return PyAxial { q, r };
+    }
+
+    fn __repr__(&self) -> String {
+        return format!(
+            "Axial({})",
+            Axial {
+                q: self.q,
+                r: self.r
+            }
+        );
+    }
+
+    #[pyo3(name = "to_cube")]
+    fn convert_to_cube(&self) -> PyResult<PyCube> {
+        let axial = Axial {
+            q: self.q,
+            r: self.r,
+        };
+        return Ok(coordinate::try_convert_axial_to_cube(axial)?.into());
+    }
+}
+
+#[pymethods]
+impl PyOffset {
+    #[new]
+    fn new(q: i32, r: i32) -> Self {
+        return PyOffset { q, r };
+    }
+
+    fn __repr__(&self) -> String {
+        return format!("Offset{}", Offset::from(*self));
+    }
+}
+
+fn check_pairs<T>(array: &PyReadonlyArray2<'_, T>) -> PyResult<()>
+where
+    T: numpy::Element,
+{
+    if array.shape()[1] != 2 {
+        return Err(PyValueError::new_err("expected an array of shape (n, 2)"));
+    }
+    return Ok(());
+}
+
+#[pymethods]
+impl PyLayout {
+    #[new]
+    fn new(orientation: PyOrientation, width: f64, height: f64) -> Self {
+        return PyLayout {
+            orientation,
+            width,
+            height,
+        };
+    }
+
+    fn offset_to_cube(&self, offset: PyOffset) -> PyResult<PyCube> {
+        let orientation = Orientation::from(self.orientation);
+        return Ok(orientation
+            .try_convert_offset_to_cube(Offset::from(offset))?
+            .into());
+    }
+
+    fn cube_to_offset(&self, cube: PyCube) -> PyResult<PyOffset> {
+        let orientation = Orientation::from(self.orientation);
+        return Ok(orientation
+            .try_convert_cube_to_offset(Cube::from(cube))?
+            .into());
+    }
+
+    fn offset_to_point(&self, offset: PyOffset) -> PyResult<(f64, f64)> {
+        let orientation = Orientation::from(self.orientation);
+        let size = (self.width, self.height);
+        return Ok(orientation.try_convert_offset_to_point(size, Offset::from(offset))?);
+    }
+
+    // points that aren't finite raise OverflowError as well
+    fn point_to_offset(&self, x: f64, y: f64) -> PyResult<PyOffset> {
+        let orientation = Orientation::from(self.orientation);
+        let size = (self.width, self.height);
+        let offset: Offset<i32> = orientation.try_convert_point_to_offset(size, (x, y))?;
+        return Ok(offset.into());
+    }
+
+    // (n, 2) float points to (n, 2) int32 offsets
+    fn points_to_offsets<'py>(
+        &self,
+        py: Python<'py>,
+        points: PyReadonlyArray2<'py, f64>,
+    ) -> PyResult<Bound<'py, PyArray2<i32>>> {
+        check_pairs(&points)?;
+        let orientation = Orientation::from(self.orientation);
+        let size = (self.width, self.height);
+        let view = points.as_array();
+        let mut offsets = Vec::with_capacity(view.len());
+        for point in view.rows() {
+            let offset: Offset<i32> =
+                orientation.try_convert_point_to_offset(size, (point[0], point[1]))?;
+            offsets.extend([offset.q, offset.r]);
+        }
+        let offsets = Array2::from_shape_vec((view.nrows(), 2), offsets).unwrap();
+        return Ok(offsets.into_pyarray(py));
+    }
+
+    // (n, 2) int32 offsets to (n, 2) float points
+    fn offsets_to_points<'py>(
+        &self,
+        py: Python<'py>,
+        offsets: PyReadonlyArray2<'py, i32>,
+    ) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        check_pairs(&offsets)?;
+        let orientation = Orientation::from(self.orientation);
+        let size = (self.width, self.height);
+        let view = offsets.as_array();
+        let mut points = Vec::with_capacity(view.len());
+        for offset in view.rows() {
+            let offset = Offset {
+                q: offset[0],
+                r: offset[1],
+            };
+            let (x, y) = orientation.try_convert_offset_to_point(size, offset)?;
+            points.extend([x, y]);
+        }
+        let points = Array2::from_shape_vec((view.nrows(), 2), points).unwrap();
+        return Ok(points.into_pyarray(py));
+    }
+}
+
+// costs has shape (height, width) and is indexed [r, q], values <= 0 mark obstacles
+// returns the path from start to goal, both included, and its total cost, or None
+#[pyfunction]
+fn find_path(
+    orientation: PyOrientation,
+    costs: PyReadonlyArray2<'_, i64>,
+    start: PyOffset,
+    goal: PyOffset,
+) -> Option<(Vec<PyOffset>, u32)> {
+    let costs = costs.as_array();
+    let size = (costs.shape()[1], costs.shape()[0]);
+    let cost = |offset| {
+        let index = index::convert_offset_to_index(size, offset)?;
+        let cost = costs[(index / size.0, index % size.0)];
+        return u32::try_from(cost).ok().filter(|cost| *cost > 0);
+    };
+    let (path, distance) =
+        crate::path::find_path(orientation.into(), size, start.into(), goal.into(), cost)?;
+    return Some((path.into_iter().map(PyOffset::from).collect(), distance));
+}
+
+#[pymodule]
+fn hex(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyOrientation>()?;
+    module.add_class::<PyCube>()?;
+    module.add_class::<PyAxial>()?;
+    module.add_class::<PyOffset>()?;
+    module.add_class::<PyLayout>()?;
+    module.add_function(wrap_pyfunction!(find_path, module)?)?;
+    return Ok(());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pyo3::ffi::c_str;
+
+    fn run(code: &std::ffi::CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "hex").unwrap();
+            hex(&module).unwrap();
+            let globals = pyo3::types::PyDict::new(py);
+            globals.set_item("hex", module).unwrap();
+            py.run(code, Some(&globals), None).unwrap();
+        });
+    }
+
+    #[test]
+    fn classes() {
+        run(c_str!(
+            r#"
+cube = hex.Cube(1, -2)
+assert cube.s == 1
+assert repr(cube) == "Cube(1,-2,1)"
+assert cube.to_axial().to_cube() == cube
+assert cube.distance(hex.Cube(0, 0)) == 2
+assert len(cube.within_range(2)) == 19
+assert len(cube.neighbors()) == 6
+assert cube.line_to(hex.Cube(4, -2))[-1] == hex.Cube(4, -2)
+assert len({cube, hex.Cube(1, -2, 1)}) == 1
+for q, r, s in [(1, 2, 3), (2**31 - 1, 2**31 - 1, 2)]:
+    try:
+        hex.Cube(q, r, s)
+        assert False
+    except ValueError:
+        pass
+
+# i32 overflow raises OverflowError instead of panicking
+big = hex.Cube(2**31 - 1, -(2**31 - 1))
+for overflow in [
+    lambda: hex.Cube(-2**31, 0),
+    lambda: big + hex.Cube(1, -1),
+    lambda: big - hex.Cube(-1, 1),
+    lambda: big.neighbors(),
+    lambda: big.within_range(1),
+    lambda: hex.Cube(0, 0).within_range(2**31 - 1),
+    lambda: big.distance(hex.Cube(-1, 0)),
+    lambda: big.line_to(hex.Cube(-1, 0)),
+    lambda: hex.Axial(-2**31, 0).to_cube(),
+    lambda: hex.Layout(hex.Orientation.Flat, 42.0, 30.0).offset_to_cube(hex.Offset(-2**31, -2**31)),
+    lambda: hex.Layout(hex.Orientation.Flat, 42.0, 30.0).point_to_offset(float("nan"), 0.0),
+]:
+    try:
+        overflow()
+        assert False
+    except OverflowError:
+        pass
+
+layout = hex.Layout(hex.Orientation.Pointy, 42.0, 30.0)
+offset = hex.Offset(3, -2)
+assert layout.cube_to_offset(layout.offset_to_cube(offset)) == offset
+assert layout.point_to_offset(*layout.offset_to_point(offset)) == offset
+"#
+        ));
+    }
+
+    // not ignored, a missing numpy fails the test with a message instead of skipping the batch conversions
+    #[test]
+    fn batch() {
+        Python::initialize();
+        Python::attach(|py| {
+            if let Err(err) = py.import("numpy") {
+                panic!("the batch test needs the numpy python package, install it with `pip install numpy`: {err}");
+            }
+        });
+        run(c_str!(
+            r#"
+import numpy
+for orientation in [hex.Orientation.Flat, hex.Orientation.Pointy]:
+    layout = hex.Layout(orientation, 42.0, 30.0)
+    offsets = numpy.array([[0, 0], [3, -2], [-5, 4]], dtype=numpy.int32)
+    points = layout.offsets_to_points(offsets)
+    assert points.shape == (3, 2)
+    assert (layout.points_to_offsets(points) == offsets).all()
+
+costs = numpy.ones((6, 8), dtype=numpy.int64)
+costs[0:5, 4] = 0
+path, cost = hex.find_path(hex.Orientation.Pointy, costs, hex.Offset(0, 0), hex.Offset(7, 0))
+assert path[0] == hex.Offset(0, 0) and path[-1] == hex.Offset(7, 0)
+assert cost == len(path) - 1
+costs[5, 4] = 0
+assert hex.find_path(hex.Orientation.Pointy, costs, hex.Offset(0, 0), hex.Offset(7, 0)) is None
+"#
+        ));
+    }
+}
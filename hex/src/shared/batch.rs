@@ -0,0 +1,129 @@
+// slice versions of the conversions, the loops have no early exits or branches so they can auto-vectorize
+// on x86_64 the offset to point loops compile to packed SSE2, the point to offset loops only partly since the
+// saturating f32 to i32 casts stay scalar, the speedup is measured in benches/batch.rs
+// every function panics if the input and output lengths differ, like copy_from_slice
+
+use crate::{shared::neighbor::*, structs::*};
+use core::ops::Neg;
+
+// round half away from zero like f32::round, which is a libcall that blocks vectorization
+// x minus its truncation is exact, so comparing it to 0.5 gives the same result
+// only for |x| < 2^31 though, x as i32 saturates above that and turns NaN into 0,
+// while round::round_cube rounds first, so the two differ outside of i32
+fn round_select(x: f32) -> i32 {
+    let truncated = x as i32;
+    let fraction = x - truncated as f32;
+    let step = if fraction >= 0.5 {
+        1
+    } else if fraction <= -0.5 {
+        -1
+    } else {
+        0
+    };
+    return truncated + step;
+}
+
+fn abs_select(x: f32) -> f32 {
+    return if x < 0.0 { -x } else { x };
+}
+
+// the same result as round::round_axial for q and r within i32, with the comparisons turned into selects
+pub(crate) fn round_axial_select(q: f32, r: f32) -> Axial<i32> {
+    let s = -q - r;
+    let (round_q, round_r, round_s) = (round_select(q), round_select(r), round_select(s));
+    let q_diff = abs_select(round_q as f32 - q);
+    let r_diff = abs_select(round_r as f32 - r);
+    let s_diff = abs_select(round_s as f32 - s);
+    let (q, r, s) = (round_q, round_r, round_s);
+
+    let fix_q = q_diff > r_diff && q_diff > s_diff;
+    let fix_r = !fix_q && r_diff > s_diff;
+    let q = if fix_q { -r - s } else { q };
+    let r = if fix_r { -q - s } else { r };
+    return Axial { q, r };
+}
+
+pub fn convert_cubes_to_axials<T: Copy>(cubes: &[Cube<T>], axials: &mut [Axial<T>]) {
+    assert_eq!(cubes.len(), axials.len());
+    for (cube, axial) in cubes.iter().zip(axials.iter_mut()) {
+        *axial = Axial {
+            q: cube.q,
+            r: cube.r,
+        };
+    }
+}
+
+pub fn convert_axials_to_cubes<T>(axials: &[Axial<T>], cubes: &mut [Cube<T>])
+where
+    T: num::Num + Neg<Output = T> + Copy,
+{
+    assert_eq!(axials.len(), cubes.len());
+    for (axial, cube) in axials.iter().zip(cubes.iter_mut()) {
+        *cube = Cube {
+            q: axial.q,
+            r: axial.r,
+            s: -axial.q - axial.r,
+        };
+    }
+}
+
+// the distance from src to every cube in dsts
+pub fn calculate_distances<T: Number>(src: Cube<T>, dsts: &[Cube<T>], distances: &mut [T]) {
+    assert_eq!(dsts.len(), distances.len());
+    for (dst, distance) in dsts.iter().zip(distances.iter_mut()) {
+        *distance = calculate_distance(src, *dst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::round::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn round() {
+        for i in -40..40 {
+            for j in -40..40 {
+                let q = i as f32 * 0.137;
+                let r = j as f32 * 0.211 - 0.5;
+                let exp: Axial<i32> = round_axial(Axial { q, r });
+                assert_eq!(round_axial_select(q, r), exp, "{} {}", q, r);
+            }
+        }
+
+        let halves = [-2.5, -1.5, -0.5, 0.5, 1.5, 2.5];
+        for q in halves {
+            for r in halves {
+                let exp: Axial<i32> = round_axial(Axial { q, r });
+                assert_eq!(round_axial_select(q, r), exp, "{} {}", q, r);
+            }
+        }
+    }
+
+    #[test]
+    fn slices() {
+        let cubes: Vec<Cube<i32>> = get_cubes_within_range(Cube { q: 1, r: -3, s: 2 }, 3).collect();
+        let mut axials = vec![Axial { q: 0, r: 0 }; cubes.len()];
+        convert_cubes_to_axials(&cubes, &mut axials);
+        let mut result = vec![Cube { q: 0, r: 0, s: 0 }; cubes.len()];
+        convert_axials_to_cubes(&axials, &mut result);
+        assert_eq!(result, cubes);
+
+        let mut distances = vec![0; cubes.len()];
+        calculate_distances(Cube { q: 0, r: 0, s: 0 }, &cubes, &mut distances);
+        for (cube, distance) in cubes.iter().zip(distances) {
+            assert_eq!(
+                calculate_distance(Cube { q: 0, r: 0, s: 0 }, *cube),
+                distance
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn length() {
+        convert_cubes_to_axials(&[Cube { q: 0, r: 0, s: 0 }], &mut []);
+    }
+}
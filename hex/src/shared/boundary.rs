@@ -0,0 +1,103 @@
+use crate::{shared::neighbor::*, structs::*};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// edges between a cube of the region and its neighbor outside the region
+// (cube, i) is the edge shared with cube + get_cube_direction_vectors()[i]
+pub fn get_boundary_edges<T>(region: &HashSet<Cube<T>>) -> Vec<(Cube<T>, usize)>
+where
+    T: Number + Hash,
+{
+    let directions = get_cube_direction_vectors();
+    let mut edges = Vec::new();
+    for cube in region {
+        for (i, direction) in directions.iter().enumerate() {
+            if !region.contains(&(*cube + *direction)) {
+                edges.push((*cube, i));
+            }
+        }
+    }
+    return edges;
+}
+
+// corner i sits between the neighbors in direction i and direction i + 1
+// 3 * cube + direction i + direction i + 1 is the same for every cube sharing the corner
+fn get_corner_key<T: Number>(cube: Cube<T>, corner: usize) -> Cube<T> {
+    let directions = get_cube_direction_vectors();
+    let three = num::one::<T>() + num::one() + num::one();
+    let scaled = Cube {
+        q: cube.q * three,
+        r: cube.r * three,
+        s: cube.s * three,
+    };
+    return scaled + directions[corner % 6] + directions[(corner + 1) % 6];
+}
+
+// closed loops of boundary edges, each edge (cube, i) runs from corner i to corner i - 1
+// directions go clockwise with y pointing up, so outer boundaries run counterclockwise and holes clockwise
+pub fn get_boundary_loops<T>(region: &HashSet<Cube<T>>) -> Vec<Vec<(Cube<T>, usize)>>
+where
+    T: Number + Hash,
+{
+    // every corner has 3 cubes around it, so at most one boundary edge starts from it
+    let mut edges: HashMap<Cube<T>, (Cube<T>, usize)> = get_boundary_edges(region)
+        .into_iter()
+        .map(|(cube, i)| (get_corner_key(cube, i), (cube, i)))
+        .collect();
+
+    let mut loops = Vec::new();
+    while let Some(start) = edges.keys().next().copied() {
+        let mut boundary = Vec::new();
+        let mut key = start;
+        while let Some((cube, i)) = edges.remove(&key) {
+            boundary.push((cube, i));
+            key = get_corner_key(cube, i + 5);
+        }
+        loops.push(boundary);
+    }
+    return loops;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORIGIN: Cube<i32> = Cube { q: 0, r: 0, s: 0 };
+
+    #[test]
+    fn single() {
+        let region = HashSet::from([ORIGIN]);
+        assert_eq!(get_boundary_edges(&region).len(), 6);
+        let loops = get_boundary_loops(&region);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 6);
+    }
+
+    #[test]
+    fn hole() {
+        let region: HashSet<_> = get_nth_nearest_cubes(ORIGIN, 1).collect();
+        assert_eq!(get_boundary_edges(&region).len(), 24);
+
+        let mut loops = get_boundary_loops(&region);
+        loops.sort_by_key(|boundary| boundary.len());
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].len(), 6);
+        assert_eq!(loops[1].len(), 18);
+        for (cube, i) in &loops[0] {
+            let direction = get_cube_direction_vectors()[*i];
+            assert_eq!(*cube + direction, ORIGIN);
+        }
+    }
+
+    #[test]
+    fn connected() {
+        let region: HashSet<_> = get_cubes_within_range(ORIGIN, 2).collect();
+        let loops = get_boundary_loops(&region);
+        assert_eq!(loops.len(), 1);
+        let boundary = &loops[0];
+        for (index, (cube, i)) in boundary.iter().enumerate() {
+            let (next, j) = boundary[(index + 1) % boundary.len()];
+            assert_eq!(get_corner_key(*cube, i + 5), get_corner_key(next, j));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{shared::neighbor::*, structs::*};
+
+// chunks are hexagons of the given radius which tile the plane without gaps
+// a chunk is identified by a cube on the coarser grid, so neighbor functions also work on chunks
+// https://observablehq.com/@sanderevers/hexagon-tiling-of-an-hexagonal-grid
+pub fn get_chunk<T>(cube: Cube<T>, radius: T) -> Cube<T>
+where
+    T: Number + num::Integer,
+{
+    let one = num::one::<T>();
+    let three = one + one + one;
+    let area = three * radius * radius + three * radius + one;
+    let shift = three * radius + one + one;
+
+    let q = (cube.r + shift * cube.q).div_floor(&area);
+    let r = (cube.s + shift * cube.r).div_floor(&area);
+    let s = (cube.q + shift * cube.s).div_floor(&area);
+
+    let chunk_q = (one + q - r).div_floor(&three);
+    let chunk_r = (one + r - s).div_floor(&three);
+    let chunk_s = (one + s - q).div_floor(&three);
+    return Cube {
+        q: chunk_q,
+        r: chunk_r,
+        s: chunk_s,
+    };
+}
+
+pub fn get_chunk_center<T>(chunk: Cube<T>, radius: T) -> Cube<T>
+where
+    T: Number,
+{
+    let next = radius + num::one();
+    let q = chunk.q * next - chunk.s * radius;
+    let r = chunk.r * next - chunk.q * radius;
+    let s = chunk.s * next - chunk.r * radius;
+    return Cube { q, r, s };
+}
+
+// returns (chunk, local) where local is relative to the chunk center
+pub fn convert_cube_to_chunk<T>(cube: Cube<T>, radius: T) -> (Cube<T>, Cube<T>)
+where
+    T: Number + num::Integer,
+{
+    let chunk = get_chunk(cube, radius);
+    let local = cube - get_chunk_center(chunk, radius);
+    return (chunk, local);
+}
+
+pub fn convert_chunk_to_cube<T>(chunk: Cube<T>, local: Cube<T>, radius: T) -> Cube<T>
+where
+    T: Number,
+{
+    return get_chunk_center(chunk, radius) + local;
+}
+
+pub fn get_neighbor_chunks<T: Number>(chunk: Cube<T>) -> [Cube<T>; 6] {
+    return get_cube_direction_vectors().map(|direction| chunk + direction);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::coordinate::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn round_trip() {
+        for radius in 0..5 {
+            for cube in get_cubes_within_range(Cube { q: 0, r: 0, s: 0 }, 20) {
+                let (chunk, local) = convert_cube_to_chunk(cube, radius);
+                assert_eq!(chunk.q + chunk.r + chunk.s, 0, "{:?}", chunk);
+                assert!(
+                    calculate_distance(local, Cube { q: 0, r: 0, s: 0 }) <= radius,
+                    "cube: {:?}, chunk: {:?}, local: {:?}",
+                    cube,
+                    chunk,
+                    local
+                );
+                assert_eq!(convert_chunk_to_cube(chunk, local, radius), cube);
+            }
+        }
+    }
+
+    #[test]
+    fn no_gaps() {
+        const RADIUS: i32 = 2;
+        let area = 3 * RADIUS * RADIUS + 3 * RADIUS + 1;
+
+        let mut counts = HashMap::new();
+        for cube in get_cubes_within_range(Cube { q: 0, r: 0, s: 0 }, 30) {
+            *counts.entry(get_chunk(cube, RADIUS)).or_insert(0) += 1;
+        }
+        let origin = convert_axial_to_cube(Axial { q: 0, r: 0 });
+        for chunk in get_cubes_within_range(origin, 3) {
+            assert_eq!(counts[&chunk], area, "{:?}", chunk);
+        }
+    }
+
+    #[test]
+    fn neighbors() {
+        const RADIUS: i32 = 3;
+        let chunk = Cube { q: 1, r: -2, s: 1 };
+        let center = get_chunk_center(chunk, RADIUS);
+        assert_eq!(get_chunk(center, RADIUS), chunk);
+
+        for neighbor in get_neighbor_chunks(chunk) {
+            let neighbor_center = get_chunk_center(neighbor, RADIUS);
+            assert_eq!(calculate_distance(center, neighbor_center), 2 * RADIUS + 1);
+            assert_eq!(get_chunk(neighbor_center, RADIUS), neighbor);
+        }
+    }
+}
@@ -1,5 +1,7 @@
-use crate::structs::*;
-use std::ops::{Add, Neg, Sub};
+use crate::{error::*, structs::*};
+use core::ops::{Add, Neg, Sub};
+use num::traits::{CheckedNeg, SaturatingAdd, SaturatingSub, WrappingAdd, WrappingSub};
+use num::{CheckedAdd, CheckedSub};
 
 // https://www.redblobgames.com/grids/hexagons/#conversions-axial
 pub fn convert_cube_to_axial<T>(cube: Cube<T>) -> Axial<T> {
@@ -19,10 +21,90 @@ where
     return Cube { q, r, s };
 }
 
-impl<T: num::Num> Add for Cube<T> {
+pub fn try_convert_axial_to_cube<T>(axial: Axial<T>) -> Result<Cube<T>, OverflowError>
+where
+    T: num::Num + CheckedNeg + CheckedSub + Copy,
+{
+    let q = axial.q;
+    let r = axial.r;
+    let s = q.checked_neg().and_then(|s| s.checked_sub(&r));
+    let s = s.ok_or(OverflowError)?;
+    return Ok(Cube { q, r, s });
+}
+
+impl<T: num::Num + Clone> Cube<T> {
+    pub fn new(q: T, r: T) -> Self
+    where
+        T: Neg<Output = T>,
+    {
+        let s = -q.clone() - r.clone();
+        return Cube { q, r, s };
+    }
+
+    // fractional cubes, e.g. for round_cube, only satisfy q + r + s == 0 approximately
+    // build them with Cube::new or the public fields instead
+    pub fn try_new(q: T, r: T, s: T) -> Result<Self, InvalidCube>
+    where
+        T: CheckedAdd,
+    {
+        let cube = Cube { q, r, s };
+        if !cube.is_valid() {
+            return Err(InvalidCube);
+        }
+        return Ok(cube);
+    }
+
+    // checked, since a valid cube can have q + r beyond the limits of T, e.g. s == i16::MIN
+    // adding the two components of opposite sign first never overflows, so one of the orders does not
+    pub fn is_valid(&self) -> bool
+    where
+        T: CheckedAdd,
+    {
+        let (q, r, s) = (&self.q, &self.r, &self.s);
+        return [(q, r, s), (q, s, r), (r, s, q)]
+            .into_iter()
+            .any(|(a, b, c)| {
+                let sum = a.checked_add(b).and_then(|sum| sum.checked_add(c));
+                return sum == Some(T::zero());
+            });
+    }
+}
+
+// is_valid for any cube Add and Sub take, floats have no checked arithmetic
+// the components of opposite sign are added first, and the last addition only when the signs differ again
+fn is_sum_zero<T: num::Num + PartialOrd + Clone>(cube: &Cube<T>) -> bool {
+    let zero = T::zero();
+    let (q, r, s) = (cube.q.clone(), cube.r.clone(), cube.s.clone());
+    let (pair, rest) = if (q < zero) != (r < zero) {
+        (q + r, s)
+    } else if (q < zero) != (s < zero) {
+        (q + s, r)
+    } else {
+        return q == zero && r == zero && s == zero;
+    };
+    if (pair < zero) == (rest < zero) {
+        return pair == zero && rest == zero;
+    }
+    return pair + rest == zero;
+}
+
+// only integer cubes are checked, since fractional ones are valid only approximately
+// one / two is zero only for integers
+fn debug_assert_valid<T: num::Num + PartialOrd + Clone>(cube: &Cube<T>) {
+    debug_assert!(
+        T::one() / (T::one() + T::one()) != T::zero() || is_sum_zero(cube),
+        "q + r + s != 0"
+    );
+}
+
+// like the primitives, overflow panics in debug builds and wraps in release builds
+// checked_add and checked_sub below return None instead
+impl<T: num::Num + PartialOrd + Clone> Add for Cube<T> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
+        debug_assert_valid(&self);
+        debug_assert_valid(&rhs);
         Cube {
             q: self.q + rhs.q,
             r: self.r + rhs.r,
@@ -31,10 +113,13 @@ impl<T: num::Num> Add for Cube<T> {
     }
 }
 
-impl<T: num::Num> Sub for Cube<T> {
+// overflows like Add
+impl<T: num::Num + PartialOrd + Clone> Sub for Cube<T> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
+        debug_assert_valid(&self);
+        debug_assert_valid(&rhs);
         Cube {
             q: self.q - rhs.q,
             r: self.r - rhs.r,
@@ -42,3 +127,223 @@ impl<T: num::Num> Sub for Cube<T> {
         }
     }
 }
+
+impl<T: num::Num + PartialOrd + Copy> Cube<T> {
+    pub fn checked_add(self, rhs: Self) -> Option<Self>
+    where
+        T: CheckedAdd,
+    {
+        debug_assert_valid(&self);
+        debug_assert_valid(&rhs);
+        let q = self.q.checked_add(&rhs.q)?;
+        let r = self.r.checked_add(&rhs.r)?;
+        let s = self.s.checked_add(&rhs.s)?;
+        return Some(Cube { q, r, s });
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self>
+    where
+        T: CheckedSub,
+    {
+        debug_assert_valid(&self);
+        debug_assert_valid(&rhs);
+        let q = self.q.checked_sub(&rhs.q)?;
+        let r = self.r.checked_sub(&rhs.r)?;
+        let s = self.s.checked_sub(&rhs.s)?;
+        return Some(Cube { q, r, s });
+    }
+
+    // each component is clamped on its own, so q + r + s == 0 no longer holds after clamping
+    pub fn saturating_add(self, rhs: Self) -> Self
+    where
+        T: SaturatingAdd,
+    {
+        let q = self.q.saturating_add(&rhs.q);
+        let r = self.r.saturating_add(&rhs.r);
+        let s = self.s.saturating_add(&rhs.s);
+        return Cube { q, r, s };
+    }
+
+    // each component is clamped on its own, so q + r + s == 0 no longer holds after clamping
+    pub fn saturating_sub(self, rhs: Self) -> Self
+    where
+        T: SaturatingSub,
+    {
+        let q = self.q.saturating_sub(&rhs.q);
+        let r = self.r.saturating_sub(&rhs.r);
+        let s = self.s.saturating_sub(&rhs.s);
+        return Cube { q, r, s };
+    }
+
+    // q + r + s == 0 still holds, since wrapping arithmetic is modular
+    pub fn wrapping_add(self, rhs: Self) -> Self
+    where
+        T: WrappingAdd,
+    {
+        let q = self.q.wrapping_add(&rhs.q);
+        let r = self.r.wrapping_add(&rhs.r);
+        let s = self.s.wrapping_add(&rhs.s);
+        return Cube { q, r, s };
+    }
+
+    pub fn wrapping_sub(self, rhs: Self) -> Self
+    where
+        T: WrappingSub,
+    {
+        let q = self.q.wrapping_sub(&rhs.q);
+        let r = self.r.wrapping_sub(&rhs.r);
+        let s = self.s.wrapping_sub(&rhs.s);
+        return Cube { q, r, s };
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn constructor() {
+        assert_eq!(Cube::new(1, -3), Cube { q: 1, r: -3, s: 2 });
+        assert_eq!(Cube::try_new(1, -3, 2), Ok(Cube { q: 1, r: -3, s: 2 }));
+        assert_eq!(Cube::try_new(1, -3, 3), Err(InvalidCube));
+        assert!(is_sum_zero(&Cube::new(0.25, 0.5)));
+    }
+
+    #[test]
+    fn limits() {
+        assert_eq!(Cube::try_new(i16::MAX, i16::MAX, 2), Err(InvalidCube));
+        assert_eq!(Cube::try_new(i16::MIN, i16::MIN, 0), Err(InvalidCube));
+        assert!(Cube::try_new(27307_i16, 5461, i16::MIN).is_ok());
+        assert!(Cube::try_new(i16::MIN, i16::MAX, 1).is_ok());
+        assert!(Cube::try_new(i32::MAX, 1, i32::MIN).is_ok());
+        assert_eq!(
+            Cube::try_new(i32::MAX, i32::MAX, i32::MIN),
+            Err(InvalidCube)
+        );
+
+        let cube = Cube {
+            q: 27307_i16,
+            r: 5461,
+            s: i16::MIN,
+        };
+        assert!(is_sum_zero(&cube));
+        assert!(!is_sum_zero(&Cube {
+            q: i16::MAX,
+            r: i16::MAX,
+            s: 2
+        }));
+        assert_eq!(
+            cube + Cube::new(-1, 0),
+            Cube {
+                q: 27306,
+                r: 5461,
+                s: -32767
+            }
+        );
+        assert_eq!(cube.checked_sub(Cube::new(0, -1)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "q + r + s != 0")]
+    #[cfg(debug_assertions)]
+    fn invalid_arithmetic() {
+        let _ = Cube { q: 1, r: 0, s: 0 } + Cube::new(1, -1);
+    }
+
+    #[test]
+    fn fractional_arithmetic() {
+        let a = Cube {
+            q: 0.1_f32,
+            r: 0.2,
+            s: -0.3,
+        };
+        let b = a + a - a;
+        assert!((b.q - a.q).abs() < 1e-6);
+    }
+
+    #[test]
+    fn checked() {
+        let max = Cube {
+            q: i16::MAX,
+            r: i16::MIN + 1,
+            s: 0,
+        };
+        let one = Cube { q: 1, r: -1, s: 0 };
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(max.checked_sub(one), Some(max - one));
+        assert_eq!(Cube { q: -2, r: 1, s: 1 }.checked_sub(max), None);
+        assert_eq!(
+            max.saturating_add(one),
+            Cube {
+                q: i16::MAX,
+                r: i16::MIN,
+                s: 0
+            }
+        );
+        let wrapped = max.wrapping_add(one);
+        assert_eq!(wrapped.q.wrapping_add(wrapped.r).wrapping_add(wrapped.s), 0);
+    }
+
+    #[test]
+    fn try_axial_to_cube() {
+        let axial = Axial { q: i16::MIN, r: 0 };
+        assert_eq!(try_convert_axial_to_cube(axial), Err(OverflowError));
+        let axial = Axial { q: i16::MAX, r: 2 };
+        assert_eq!(try_convert_axial_to_cube(axial), Err(OverflowError));
+        let axial = Axial { q: i16::MAX, r: 0 };
+        assert_eq!(
+            try_convert_axial_to_cube(axial),
+            Ok(Cube {
+                q: i16::MAX,
+                r: 0,
+                s: -i16::MAX
+            })
+        );
+    }
+
+    // shared with the other proptests, far enough from the i32 limits that sums of a few cubes don't overflow
+    pub(crate) fn cube() -> impl Strategy<Value = Cube<i32>> {
+        return (-1_000_000..1_000_000, -1_000_000..1_000_000).prop_map(|(q, r)| Cube::new(q, r));
+    }
+
+    // checked arithmetic on i16 agrees with i32 arithmetic narrowed back to i16
+    fn assert_checked_overflow(q: i16, r: i16, dq: i16, dr: i16) -> Result<(), TestCaseError> {
+        let a = Cube::new(q as i32, r as i32);
+        let b = Cube::new(dq as i32, dr as i32);
+        let narrow = |cube: Cube<i32>| -> Option<Cube<i16>> {
+            let q = i16::try_from(cube.q).ok()?;
+            let r = i16::try_from(cube.r).ok()?;
+            let s = i16::try_from(cube.s).ok()?;
+            return Some(Cube { q, r, s });
+        };
+        let (Some(a16), Some(b16)) = (narrow(a), narrow(b)) else {
+            return Ok(());
+        };
+        prop_assert_eq!(a16.checked_add(b16), narrow(a + b));
+        prop_assert_eq!(a16.checked_sub(b16), narrow(a - b));
+        return Ok(());
+    }
+
+    // s is i16::MIN, so q + r overflows although the cube is valid
+    #[test]
+    fn checked_overflow_regression() {
+        assert_checked_overflow(27307, 5461, 0, 0).unwrap();
+        assert_checked_overflow(i16::MIN, 1, 0, 0).unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn arithmetic_invariant(a in cube(), b in cube()) {
+            prop_assert!((a + b).is_valid());
+            prop_assert!((a - b).is_valid());
+            prop_assert_eq!(a + b - b, a);
+            prop_assert_eq!(a.checked_add(b), Some(a + b));
+        }
+
+        #[test]
+        fn checked_overflow(q in any::<i16>(), r in any::<i16>(), dq in any::<i16>(), dr in any::<i16>()) {
+            assert_checked_overflow(q, r, dq, dr)?;
+        }
+    }
+}
@@ -0,0 +1,183 @@
+// edge i of a cube is shared with its neighbor in direction i
+// corner i of a cube sits between the neighbors in direction i and i + 1, matching convert_axial_to_corners
+// https://www.redblobgames.com/grids/parts/
+
+use crate::{shared::neighbor::*, structs::*};
+
+impl<T: Number> HexEdge<T> {
+    pub fn new(cube: Cube<T>, direction: usize) -> Self {
+        let direction = direction % 6;
+        if direction < 3 {
+            return HexEdge { cube, direction };
+        }
+        let cube = cube + get_cube_direction_vectors()[direction];
+        let direction = direction - 3;
+        return HexEdge { cube, direction };
+    }
+
+    pub fn cube(&self) -> Cube<T> {
+        return self.cube;
+    }
+
+    pub fn direction(&self) -> usize {
+        return self.direction;
+    }
+}
+
+impl<T: Number> HexVertex<T> {
+    pub fn new(cube: Cube<T>, corner: usize) -> Self {
+        let directions = get_cube_direction_vectors();
+        let (cube, corner) = match corner % 6 {
+            0 => (cube, 0),
+            1 => (cube, 1),
+            2 => (cube - directions[0], 0),
+            3 => (cube - directions[1], 1),
+            4 => (cube - directions[1], 0),
+            _ => (cube - directions[2], 1),
+        };
+        return HexVertex { cube, corner };
+    }
+
+    pub fn cube(&self) -> Cube<T> {
+        return self.cube;
+    }
+
+    pub fn corner(&self) -> usize {
+        return self.corner;
+    }
+}
+
+pub fn get_cube_edges<T: Number>(cube: Cube<T>) -> [HexEdge<T>; 6] {
+    return core::array::from_fn(|i| HexEdge::new(cube, i));
+}
+
+pub fn get_cube_vertices<T: Number>(cube: Cube<T>) -> [HexVertex<T>; 6] {
+    return core::array::from_fn(|i| HexVertex::new(cube, i));
+}
+
+pub fn get_edge_cubes<T: Number>(edge: HexEdge<T>) -> [Cube<T>; 2] {
+    let direction = get_cube_direction_vectors()[edge.direction];
+    return [edge.cube, edge.cube + direction];
+}
+
+// edge i runs from corner i - 1 to corner i
+pub fn get_edge_vertices<T: Number>(edge: HexEdge<T>) -> [HexVertex<T>; 2] {
+    return [
+        HexVertex::new(edge.cube, edge.direction + 5),
+        HexVertex::new(edge.cube, edge.direction),
+    ];
+}
+
+// the edges sharing a vertex with the given edge
+pub fn get_edge_neighbors<T: Number>(edge: HexEdge<T>) -> [HexEdge<T>; 4] {
+    let [a, b] = get_edge_vertices(edge).map(|vertex| {
+        let mut edges = get_vertex_edges(vertex)
+            .into_iter()
+            .filter(|other| *other != edge);
+        [edges.next().unwrap(), edges.next().unwrap()]
+    });
+    return [a[0], a[1], b[0], b[1]];
+}
+
+pub fn get_vertex_cubes<T: Number>(vertex: HexVertex<T>) -> [Cube<T>; 3] {
+    let directions = get_cube_direction_vectors();
+    let corner = vertex.corner;
+    return [
+        vertex.cube,
+        vertex.cube + directions[corner],
+        vertex.cube + directions[corner + 1],
+    ];
+}
+
+pub fn get_vertex_edges<T: Number>(vertex: HexVertex<T>) -> [HexEdge<T>; 3] {
+    let directions = get_cube_direction_vectors();
+    let corner = vertex.corner;
+    return [
+        HexEdge::new(vertex.cube, corner),
+        HexEdge::new(vertex.cube, corner + 1),
+        HexEdge::new(vertex.cube + directions[corner], corner + 2),
+    ];
+}
+
+// the vertices one edge away from the given vertex
+pub fn get_vertex_neighbors<T: Number>(vertex: HexVertex<T>) -> [HexVertex<T>; 3] {
+    return get_vertex_edges(vertex).map(|edge| {
+        let [a, b] = get_edge_vertices(edge);
+        if a == vertex {
+            b
+        } else {
+            a
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use std::collections::HashSet;
+
+    const ORIGIN: Cube<i32> = Cube { q: 0, r: 0, s: 0 };
+
+    #[test]
+    fn canonical() {
+        let directions = get_cube_direction_vectors();
+        for cube in get_cubes_within_range(ORIGIN, 3) {
+            for i in 0..6 {
+                let edge = HexEdge::new(cube, i);
+                assert!(edge.direction() < 3);
+                assert_eq!(edge, HexEdge::new(cube + directions[i], i + 3));
+                assert!(get_edge_cubes(edge).contains(&cube));
+
+                let vertex = HexVertex::new(cube, i);
+                assert!(vertex.corner() < 2);
+                assert_eq!(vertex, HexVertex::new(cube + directions[i], i + 2));
+                assert_eq!(
+                    vertex,
+                    HexVertex::new(cube + directions[(i + 1) % 6], i + 4)
+                );
+                assert!(get_vertex_cubes(vertex).contains(&cube));
+            }
+        }
+
+        let region: Vec<_> = get_cubes_within_range(ORIGIN, 10).collect();
+        let edges: HashSet<_> = region
+            .iter()
+            .flat_map(|cube| get_cube_edges(*cube))
+            .collect();
+        let vertices: HashSet<_> = region
+            .iter()
+            .flat_map(|cube| get_cube_vertices(*cube))
+            .collect();
+        // 3 edges per cube plus the outer ones, 6 (n + 1)^2 vertices for a hexagon of radius n
+        assert_eq!(edges.len(), 3 * region.len() + 6 * 10 + 3);
+        assert_eq!(vertices.len(), 6 * 11 * 11);
+    }
+
+    #[test]
+    fn adjacency() {
+        for cube in get_cubes_within_range(ORIGIN, 2) {
+            for edge in get_cube_edges(cube) {
+                let cubes = get_edge_cubes(edge);
+                for vertex in get_edge_vertices(edge) {
+                    let around = get_vertex_cubes(vertex);
+                    assert!(cubes.iter().all(|cube| around.contains(cube)));
+                    assert!(get_vertex_edges(vertex).contains(&edge));
+                }
+                let neighbors = get_edge_neighbors(edge);
+                assert_eq!(neighbors.iter().collect::<HashSet<_>>().len(), 4);
+                assert!(!neighbors.contains(&edge));
+            }
+            for vertex in get_cube_vertices(cube) {
+                for neighbor in get_vertex_neighbors(vertex) {
+                    assert!(get_vertex_neighbors(neighbor).contains(&vertex));
+                    let shared = get_vertex_cubes(neighbor)
+                        .into_iter()
+                        .filter(|cube| get_vertex_cubes(vertex).contains(cube))
+                        .count();
+                    assert_eq!(shared, 2);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{error::*, shared::neighbor::*, structs::*};
+use num::cast::AsPrimitive;
+
+// number of cubes within range n, aka the length of a spiral of radius n
+pub fn calculate_hexagon_size(n: usize) -> usize {
+    return 3 * n * n + 3 * n + 1;
+}
+
+pub fn try_calculate_hexagon_size(n: usize) -> Result<usize, OverflowError> {
+    let size = n
+        .checked_mul(n)
+        .and_then(|square| square.checked_add(n))
+        .and_then(|sum| sum.checked_mul(3))
+        .and_then(|sum| sum.checked_add(1));
+    return size.ok_or(OverflowError);
+}
+
+// spiral order: src first, then each ring starting from src + direction 4 * radius
+// https://www.redblobgames.com/grids/hexagons/#rings-spiral
+pub fn convert_cube_to_spiral_index<T>(src: Cube<T>, cube: Cube<T>) -> usize
+where
+    T: Number + AsPrimitive<usize>,
+{
+    let n = calculate_distance(src, cube);
+    if n == num::zero() {
+        return 0;
+    }
+    let diff = cube - src;
+    let (side, step) = if diff.r == n && diff.q < num::zero() {
+        (0, diff.q + n)
+    } else if diff.s == -n && diff.r > num::zero() {
+        (1, diff.q)
+    } else if diff.q == n && diff.s < num::zero() {
+        (2, -diff.r)
+    } else if diff.r == -n && diff.q > num::zero() {
+        (3, diff.s)
+    } else if diff.s == n && diff.r < num::zero() {
+        (4, -diff.q)
+    } else {
+        (5, diff.r)
+    };
+    let n: usize = n.as_();
+    return 1 + 3 * n * (n - 1) + side * n + step.as_();
+}
+
+pub fn convert_spiral_index_to_cube<T>(src: Cube<T>, index: usize) -> Cube<T>
+where
+    T: Number + 'static,
+    usize: AsPrimitive<T>,
+{
+    if index == 0 {
+        return src;
+    }
+    // 1 + 3n(n - 1) <= index <= 3n(n + 1)
+    let n = (3 + (12 * index - 3).isqrt()) / 6;
+    let index = index - 1 - 3 * n * (n - 1);
+    let side = index / n;
+    let step = index % n;
+
+    let directions = get_cube_direction_vectors::<T>();
+    let radius: T = n.as_();
+    let mut corner = Cube {
+        q: -radius,
+        r: radius,
+        s: num::zero(),
+    };
+    for direction in &directions[..side] {
+        corner = corner + scale(*direction, radius);
+    }
+    return src + corner + scale(directions[side], step.as_());
+}
+
+fn scale<T: Number>(cube: Cube<T>, factor: T) -> Cube<T> {
+    return Cube {
+        q: cube.q * factor,
+        r: cube.r * factor,
+        s: cube.s * factor,
+    };
+}
+
+// row-major order within a width x height rectangle
+// offsets are laid out by column q and row r in both flat and pointy, so the order is shared
+pub fn convert_offset_to_index<T>(size: (usize, usize), offset: Offset<T>) -> Option<usize>
+where
+    T: num::Num + PartialOrd + AsPrimitive<usize>,
+{
+    if offset.q < num::zero() || offset.r < num::zero() {
+        return None;
+    }
+    let q: usize = offset.q.as_();
+    let r: usize = offset.r.as_();
+    if q >= size.0 || r >= size.1 {
+        return None;
+    }
+    return Some(r * size.0 + q);
+}
+
+pub fn convert_index_to_offset<T>(size: (usize, usize), index: usize) -> Option<Offset<T>>
+where
+    T: 'static + Copy,
+    usize: AsPrimitive<T>,
+{
+    if index >= size.0 * size.1 {
+        return None;
+    }
+    let q = (index % size.0).as_();
+    let r = (index / size.0).as_();
+    return Some(Offset { q, r });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn spiral() {
+        let src = Cube { q: 2, r: -5, s: 3 };
+        let cubes: Vec<Cube<i32>> = (0..calculate_hexagon_size(5))
+            .map(|index| convert_spiral_index_to_cube(src, index))
+            .collect();
+        assert_eq!(cubes[0], src);
+        assert_eq!(cubes[1], src + Cube { q: -1, r: 1, s: 0 });
+
+        for (index, cube) in cubes.iter().enumerate() {
+            assert_eq!(cube.q + cube.r + cube.s, 0, "{:?}", cube);
+            assert_eq!(
+                convert_cube_to_spiral_index(src, *cube),
+                index,
+                "{:?}",
+                cube
+            );
+        }
+        let within: Vec<_> = get_cubes_within_range(src, 5).collect();
+        assert_eq!(within.len(), cubes.len());
+        assert!(within.iter().all(|cube| cubes.contains(cube)));
+    }
+
+    #[test]
+    fn spiral_ring_is_connected() {
+        let src = Cube { q: 0, r: 0, s: 0 };
+        for n in 1..6 {
+            let start = calculate_hexagon_size(n - 1);
+            let ring: Vec<Cube<i32>> = (start..calculate_hexagon_size(n))
+                .map(|index| convert_spiral_index_to_cube(src, index))
+                .collect();
+            assert_eq!(ring.len(), 6 * n);
+            for (i, cube) in ring.iter().enumerate() {
+                let next = ring[(i + 1) % ring.len()];
+                assert_eq!(calculate_distance(*cube, next), 1, "{:?} {:?}", cube, next);
+                assert_eq!(calculate_distance(src, *cube), n as i32);
+            }
+        }
+    }
+
+    #[test]
+    fn hexagon_size() {
+        assert_eq!(try_calculate_hexagon_size(5), Ok(calculate_hexagon_size(5)));
+        assert_eq!(try_calculate_hexagon_size(0), Ok(1));
+        assert_eq!(try_calculate_hexagon_size(usize::MAX), Err(OverflowError));
+        assert_eq!(
+            try_calculate_hexagon_size(1 << (usize::BITS / 2)),
+            Err(OverflowError)
+        );
+    }
+
+    #[test]
+    fn row_major() {
+        const SIZE: (usize, usize) = (4, 3);
+        for index in 0..12 {
+            let offset: Offset<i32> = convert_index_to_offset(SIZE, index).unwrap();
+            assert_eq!(convert_offset_to_index(SIZE, offset), Some(index));
+        }
+        assert_eq!(
+            convert_offset_to_index(SIZE, Offset { q: 3, r: 0 }),
+            Some(3)
+        );
+        assert_eq!(
+            convert_offset_to_index(SIZE, Offset { q: 0, r: 1 }),
+            Some(4)
+        );
+        assert_eq!(convert_offset_to_index(SIZE, Offset { q: 4, r: 0 }), None);
+        assert_eq!(convert_offset_to_index(SIZE, Offset { q: 0, r: -1 }), None);
+        assert_eq!(convert_index_to_offset::<i32>(SIZE, 12), None);
+    }
+}
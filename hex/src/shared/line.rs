@@ -0,0 +1,73 @@
+use crate::{shared::neighbor::*, shared::round::*, structs::*};
+use alloc::vec::Vec;
+use num::cast::AsPrimitive;
+
+pub fn lerp_cube<F: num::Float>(a: Cube<F>, b: Cube<F>, t: F) -> Cube<F> {
+    return Cube {
+        q: a.q + (b.q - a.q) * t,
+        r: a.r + (b.r - a.r) * t,
+        s: a.s + (b.s - a.s) * t,
+    };
+}
+
+// https://www.redblobgames.com/grids/hexagons/#line-drawing
+// src is nudged so points on an edge between two cubes always round the same way
+pub fn get_line_cubes<T, F>(src: Cube<T>, dst: Cube<T>) -> Vec<Cube<T>>
+where
+    T: Number + AsPrimitive<F>,
+    F: num::Float + AsPrimitive<T>,
+{
+    let n: F = calculate_distance(src, dst).as_();
+    let epsilon = F::epsilon().sqrt();
+    let a = Cube {
+        q: src.q.as_() + epsilon,
+        r: src.r.as_() + epsilon,
+        s: src.s.as_() - epsilon - epsilon,
+    };
+    let b = Cube {
+        q: dst.q.as_() + epsilon,
+        r: dst.r.as_() + epsilon,
+        s: dst.s.as_() - epsilon - epsilon,
+    };
+    if n == F::zero() {
+        return Vec::from([src]);
+    }
+    let mut cubes = Vec::new();
+    let mut i = F::zero();
+    while i <= n {
+        cubes.push(round_cube(lerp_cube(a, b, i / n)));
+        i = i + F::one();
+    }
+    return cubes;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn line() {
+        let src = Cube { q: 0, r: 0, s: 0 };
+        assert_eq!(get_line_cubes::<i32, f32>(src, src), [src]);
+
+        let dst = Cube { q: 3, r: -1, s: -2 };
+        let line = get_line_cubes::<i32, f64>(src, dst);
+        assert_eq!(line.len(), 4);
+        assert_eq!(line[0], src);
+        assert_eq!(line[3], dst);
+        for step in line.windows(2) {
+            assert_eq!(calculate_distance(step[0], step[1]), 1);
+        }
+
+        // along a straight axis there is no ambiguity
+        let dst = Cube { q: 0, r: -4, s: 4 };
+        let line = get_line_cubes::<i32, f32>(src, dst);
+        let exp: Vec<_> = (0..=4).map(|i| Cube { q: 0, r: -i, s: i }).collect();
+        assert_eq!(line, exp);
+
+        // the middle point lies on the edge between (1, -1, 0) and (1, 0, -1)
+        let dst = Cube { q: 2, r: -1, s: -1 };
+        let line = get_line_cubes::<i32, f32>(src, dst);
+        assert_eq!(line, [src, Cube { q: 1, r: 0, s: -1 }, dst]);
+    }
+}
@@ -1,3 +1,16 @@
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod boundary;
+pub mod chunk;
 pub mod coordinate;
+pub mod edge;
+pub mod index;
+pub mod line;
 pub mod neighbor;
+#[cfg(feature = "rand")]
+pub mod random;
+#[cfg(feature = "std")]
+pub mod region;
 pub mod round;
+pub mod shape;
+pub mod triangle;
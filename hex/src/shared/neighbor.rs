@@ -1,5 +1,6 @@
-use crate::structs::*;
-use std::ops::Neg;
+use crate::{error::*, structs::*};
+use core::ops::Neg;
+use num::{CheckedAdd, CheckedSub};
 
 pub trait Number: num::Num + num::Signed + Neg<Output = Self> + Ord + Copy {}
 impl<T> Number for T where T: num::Num + num::Signed + Neg<Output = Self> + Ord + Copy {}
@@ -41,15 +42,36 @@ pub fn get_cube_direction_vectors<T: Number>() -> [Cube<T>; 6] {
     ];
 }
 
+// the sum is twice the distance, so past half of T::MAX this panics in debug builds and wraps in release builds
+// use calculate_checked_distance for cubes that can be near the limits of T
 pub fn calculate_distance<T: Number>(src: Cube<T>, dst: Cube<T>) -> T {
     let one = num::one::<T>();
     let diff = src - dst;
     return (diff.q.abs() + diff.r.abs() + diff.s.abs()) / (one + one);
 }
 
+// max(|dq|, |dr|, |ds|) equals the distance above without summing, so it overflows later
+pub fn calculate_checked_distance<T>(src: Cube<T>, dst: Cube<T>) -> Option<T>
+where
+    T: Number + CheckedAdd + CheckedSub,
+{
+    let diff = src.checked_sub(dst)?;
+    let q = checked_abs(diff.q)?;
+    let r = checked_abs(diff.r)?;
+    let s = checked_abs(diff.s)?;
+    return Some(q.max(r).max(s));
+}
+
+fn checked_abs<T: Number + CheckedSub>(value: T) -> Option<T> {
+    if value < num::zero() {
+        return T::zero().checked_sub(&value);
+    }
+    return Some(value);
+}
+
 pub fn get_cubes_within_range<T: Number>(src: Cube<T>, n: T) -> impl Iterator<Item = Cube<T>>
 where
-    std::ops::Range<T>: Iterator<Item = T>,
+    core::ops::Range<T>: Iterator<Item = T>,
 {
     let one = num::one();
     (-n..(n + one)).flat_map(move |q| {
@@ -60,19 +82,43 @@ where
     })
 }
 
+// the range bounds reach 2 * n + 1 and every component of a cube in range stays within n of src
+pub fn try_get_cubes_within_range<T>(
+    src: Cube<T>,
+    n: T,
+) -> Result<impl Iterator<Item = Cube<T>>, OverflowError>
+where
+    T: Number + CheckedAdd + CheckedSub,
+    core::ops::Range<T>: Iterator<Item = T>,
+{
+    let bound = n
+        .checked_add(&n)
+        .and_then(|bound| bound.checked_add(&T::one()));
+    let fits = [src.q, src.r, src.s].iter().all(|component| {
+        component
+            .checked_sub(&n)
+            .and(component.checked_add(&n))
+            .is_some()
+    });
+    if bound.is_none() || !fits {
+        return Err(OverflowError);
+    }
+    return Ok(get_cubes_within_range(src, n));
+}
+
 pub fn get_nth_nearest_cubes<T: Number>(src: Cube<T>, n: T) -> impl Iterator<Item = Cube<T>>
 where
-    std::ops::Range<T>: Iterator<Item = T>,
+    core::ops::Range<T>: Iterator<Item = T>,
 {
-    get_cubes_within_range(src, n)
-        .into_iter()
-        .filter(move |cube| calculate_distance(src, *cube) == n)
+    get_cubes_within_range(src, n).filter(move |cube| calculate_distance(src, *cube) == n)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::flat::coordinate::*;
+    use crate::{flat::coordinate::*, shared::coordinate::test::cube};
+    use alloc::vec::Vec;
+    use proptest::prelude::*;
     use std::collections::HashSet;
 
     const WIDTH: i32 = 3;
@@ -110,6 +156,43 @@ mod test {
         assert_eq!(d, 2);
     }
 
+    #[test]
+    fn test_checked_range() {
+        let src = Cube::new(1i8, -1);
+        assert_eq!(try_get_cubes_within_range(src, 2).unwrap().count(), 19);
+        assert_eq!(try_get_cubes_within_range(src, -1).unwrap().count(), 0);
+        assert_eq!(try_get_cubes_within_range(src, 63).unwrap().count(), 12097);
+        assert!(try_get_cubes_within_range(src, 64).is_err());
+        assert!(try_get_cubes_within_range(Cube::new(100i8, -100), 28).is_err());
+        assert!(try_get_cubes_within_range(src, i8::MIN).is_err());
+    }
+
+    #[test]
+    fn test_checked_distance() {
+        let src = Cube { q: 0, r: 0, s: 0 };
+        let dst = Cube { q: 3, r: -1, s: -2 };
+        assert_eq!(calculate_checked_distance(src, dst), Some(3));
+
+        let src = Cube {
+            q: i16::MIN + 1,
+            r: 0,
+            s: i16::MAX,
+        };
+        let dst = Cube {
+            q: i16::MAX,
+            r: 0,
+            s: i16::MIN + 1,
+        };
+        assert_eq!(calculate_checked_distance(src, dst), None);
+
+        let dst = Cube {
+            q: 0,
+            r: i16::MIN + 1,
+            s: i16::MAX,
+        };
+        assert_eq!(calculate_checked_distance(src, dst), Some(i16::MAX));
+    }
+
     #[test]
     fn test_skill_type_point_1() {
         const N: i32 = 1;
@@ -657,4 +740,33 @@ mod test {
         );
         assert_eq!(result.len(), 4, "{:?}", result);
     }
+
+    proptest! {
+        #[test]
+        fn distance_metric(a in cube(), b in cube(), c in cube()) {
+            prop_assert_eq!(calculate_distance(a, a), 0);
+            prop_assert_eq!(calculate_distance(a, b), calculate_distance(b, a));
+            prop_assert!(calculate_distance(a, c) <= calculate_distance(a, b) + calculate_distance(b, c));
+            prop_assert_eq!(calculate_checked_distance(a, b), Some(calculate_distance(a, b)));
+        }
+
+        #[test]
+        fn checked_distance_extremes(q in any::<i32>(), r in any::<i32>()) {
+            let Some(s) = q.checked_neg().and_then(|q| q.checked_sub(r)) else {
+                return Ok(());
+            };
+            let origin = Cube { q: 0, r: 0, s: 0 };
+            let exp = (q as i64).abs().max((r as i64).abs()).max((s as i64).abs());
+            let result = calculate_checked_distance(origin, Cube { q, r, s });
+            prop_assert_eq!(result.map(i64::from), i32::try_from(exp).ok().map(i64::from));
+        }
+
+        #[test]
+        fn ring(src in cube(), n in 1..200) {
+            let ring: Vec<Cube<i32>> = get_nth_nearest_cubes(src, n).collect();
+            prop_assert_eq!(ring.len(), 6 * n as usize);
+            prop_assert!(ring.iter().all(|cube| cube.is_valid() && calculate_distance(src, *cube) == n));
+            prop_assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+        }
+    }
 }
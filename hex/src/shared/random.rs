@@ -0,0 +1,234 @@
+// uniform sampling without collecting the candidates, by sampling an index instead
+// see index::convert_spiral_index_to_cube and index::convert_index_to_offset for the orders
+
+use crate::{shared::index::*, shared::neighbor::*, structs::*};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use num::cast::AsPrimitive;
+use rand::Rng;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+// None if the number of cubes within n overflows usize
+pub fn sample_cube_within_range<T, R>(rng: &mut R, src: Cube<T>, n: usize) -> Option<Cube<T>>
+where
+    T: Number + 'static,
+    usize: AsPrimitive<T>,
+    R: Rng + ?Sized,
+{
+    let index = rng.gen_range(0..try_calculate_hexagon_size(n).ok()?);
+    return Some(convert_spiral_index_to_cube(src, index));
+}
+
+// None like sample_cube_within_range
+pub fn sample_nth_nearest_cube<T, R>(rng: &mut R, src: Cube<T>, n: usize) -> Option<Cube<T>>
+where
+    T: Number + 'static,
+    usize: AsPrimitive<T>,
+    R: Rng + ?Sized,
+{
+    if n == 0 {
+        return Some(src);
+    }
+    let end = try_calculate_hexagon_size(n).ok()?;
+    let index = rng.gen_range(calculate_hexagon_size(n - 1)..end);
+    return Some(convert_spiral_index_to_cube(src, index));
+}
+
+// None if the rectangle is empty
+pub fn sample_offset_within_rectangle<T, R>(rng: &mut R, size: (usize, usize)) -> Option<Offset<T>>
+where
+    T: 'static + Copy,
+    usize: AsPrimitive<T>,
+    R: Rng + ?Sized,
+{
+    let count = size.0.checked_mul(size.1)?;
+    if count == 0 {
+        return None;
+    }
+    let index = rng.gen_range(0..count);
+    return convert_index_to_offset(size, index);
+}
+
+// same cubes as shape::get_parallelogram_cubes, None if the parallelogram is empty
+pub fn sample_cube_within_parallelogram<T, R>(
+    rng: &mut R,
+    src: Cube<T>,
+    size: (usize, usize),
+) -> Option<Cube<T>>
+where
+    T: Number + 'static,
+    usize: AsPrimitive<T>,
+    R: Rng + ?Sized,
+{
+    if size.0 == 0 || size.1 == 0 {
+        return None;
+    }
+    let q: T = rng.gen_range(0..size.0).as_();
+    let r: T = rng.gen_range(0..size.1).as_();
+    return Some(src + Cube { q, r, s: -q - r });
+}
+
+// same cubes as shape::get_triangle_cubes, None if n is 0
+pub fn sample_cube_within_triangle<T, R>(rng: &mut R, src: Cube<T>, n: usize) -> Option<Cube<T>>
+where
+    T: Number + 'static,
+    usize: AsPrimitive<T>,
+    R: Rng + ?Sized,
+{
+    if n == 0 {
+        return None;
+    }
+    // counting from the tip, the jth row has j + 1 cubes
+    let count = n.checked_mul(n + 1)? / 2;
+    let index = rng.gen_range(0..count);
+    let row = ((8 * index + 1).isqrt() - 1) / 2;
+    let q: T = (n - 1 - row).as_();
+    let r: T = (index - row * (row + 1) / 2).as_();
+    return Some(src + Cube { q, r, s: -q - r });
+}
+
+// Bridson's algorithm, with candidates taken from the rings between distance and 2 * distance
+// every pair of samples is at least distance apart
+// the predicate decides which cubes can be sampled, so it must be false outside a bounded area
+#[cfg(feature = "std")]
+pub fn sample_poisson_disc<T, R, P>(
+    rng: &mut R,
+    start: Cube<T>,
+    distance: usize,
+    attempts: usize,
+    predicate: P,
+) -> Vec<Cube<T>>
+where
+    T: Number + Hash + 'static,
+    usize: AsPrimitive<T>,
+    core::ops::Range<T>: Iterator<Item = T>,
+    R: Rng + ?Sized,
+    P: Fn(Cube<T>) -> bool,
+{
+    if !predicate(start) {
+        return Vec::new();
+    }
+    let mut samples = vec![start];
+    let mut taken = HashSet::from([start]);
+    let mut active = vec![start];
+    let too_close: T = distance.saturating_sub(1).as_();
+    while !active.is_empty() {
+        let index = rng.gen_range(0..active.len());
+        let src = active[index];
+        let candidate = (0..attempts)
+            .filter_map(|_| {
+                let n = rng.gen_range(distance.max(1)..2 * distance.max(1));
+                sample_nth_nearest_cube(rng, src, n)
+            })
+            .find(|candidate| {
+                predicate(*candidate)
+                    && get_cubes_within_range(*candidate, too_close)
+                        .all(|cube| !taken.contains(&cube))
+            });
+        match candidate {
+            Some(candidate) => {
+                samples.push(candidate);
+                taken.insert(candidate);
+                active.push(candidate);
+            }
+            None => {
+                active.swap_remove(index);
+            }
+        }
+    }
+    return samples;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::shape::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::{HashMap, HashSet};
+
+    const ORIGIN: Cube<i32> = Cube { q: 0, r: 0, s: 0 };
+
+    // every candidate is sampled, and nothing else
+    fn assert_covers(expected: HashSet<Cube<i32>>, mut sample: impl FnMut() -> Cube<i32>) {
+        let mut counts = HashMap::new();
+        for _ in 0..expected.len() * 200 {
+            *counts.entry(sample()).or_insert(0) += 1;
+        }
+        assert_eq!(counts.keys().copied().collect::<HashSet<_>>(), expected);
+        for count in counts.values() {
+            assert!(*count > 100 && *count < 300, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let src = Cube::new(3, -1);
+        let expected = get_cubes_within_range(src, 2).collect();
+        assert_covers(expected, || {
+            sample_cube_within_range(&mut rng, src, 2).unwrap()
+        });
+        let expected = get_nth_nearest_cubes(src, 2).collect();
+        assert_covers(expected, || {
+            sample_nth_nearest_cube(&mut rng, src, 2).unwrap()
+        });
+        assert_eq!(sample_nth_nearest_cube(&mut rng, src, 0), Some(src));
+        assert_eq!(
+            sample_cube_within_range(&mut rng, src, usize::MAX / 2),
+            None
+        );
+        assert_eq!(sample_nth_nearest_cube(&mut rng, src, usize::MAX / 2), None);
+    }
+
+    #[test]
+    fn shapes() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let expected = get_parallelogram_cubes(ORIGIN, (3, 2)).collect();
+        assert_covers(expected, || {
+            sample_cube_within_parallelogram(&mut rng, ORIGIN, (3, 2)).unwrap()
+        });
+        let expected = get_triangle_cubes(ORIGIN, 4).collect();
+        assert_covers(expected, || {
+            sample_cube_within_triangle(&mut rng, ORIGIN, 4).unwrap()
+        });
+
+        for _ in 0..100 {
+            let offset: Offset<i32> = sample_offset_within_rectangle(&mut rng, (4, 3)).unwrap();
+            assert!(offset.q >= 0 && offset.q < 4 && offset.r >= 0 && offset.r < 3);
+        }
+
+        assert_eq!(
+            sample_offset_within_rectangle::<i32, _>(&mut rng, (0, 3)),
+            None
+        );
+        assert_eq!(
+            sample_cube_within_parallelogram(&mut rng, ORIGIN, (3, 0)),
+            None
+        );
+        assert_eq!(sample_cube_within_triangle(&mut rng, ORIGIN, 0), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn poisson_disc() {
+        const DISTANCE: usize = 3;
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples = sample_poisson_disc(&mut rng, ORIGIN, DISTANCE, 20, |cube| {
+            calculate_distance(ORIGIN, cube) <= 12
+        });
+        assert!(samples.len() > 10, "{:?}", samples);
+        for (i, a) in samples.iter().enumerate() {
+            assert!(calculate_distance(ORIGIN, *a) <= 12);
+            for b in &samples[i + 1..] {
+                assert!(
+                    calculate_distance(*a, *b) >= DISTANCE as i32,
+                    "{:?} {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{shared::boundary::*, shared::neighbor::*, structs::*};
+use num::cast::AsPrimitive;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+
+// flood fill over the six neighbors, staying within the given cubes
+fn fill<T>(
+    cubes: &HashSet<Cube<T>>,
+    start: Cube<T>,
+    visited: &mut HashSet<Cube<T>>,
+) -> HashSet<Cube<T>>
+where
+    T: Number + Hash,
+{
+    let directions = get_cube_direction_vectors();
+    let mut component = HashSet::from([start]);
+    let mut stack = vec![start];
+    visited.insert(start);
+    while let Some(cube) = stack.pop() {
+        for direction in directions {
+            let neighbor = cube + direction;
+            if cubes.contains(&neighbor) && visited.insert(neighbor) {
+                component.insert(neighbor);
+                stack.push(neighbor);
+            }
+        }
+    }
+    return component;
+}
+
+pub fn get_connected_components<T>(region: &HashSet<Cube<T>>) -> Vec<HashSet<Cube<T>>>
+where
+    T: Number + Hash,
+{
+    let mut visited = HashSet::new();
+    let mut components = Vec::new();
+    for cube in region {
+        if !visited.contains(cube) {
+            components.push(fill(region, *cube, &mut visited));
+        }
+    }
+    return components;
+}
+
+// labels every cube of the map matching the predicate with the index of its component
+// labels follow the order of the map, so the same map always gets the same labels
+pub fn label_connected_components<T, P>(
+    map: impl IntoIterator<Item = Cube<T>>,
+    predicate: P,
+) -> HashMap<Cube<T>, usize>
+where
+    T: Number + Hash,
+    P: Fn(Cube<T>) -> bool,
+{
+    let cubes: Vec<_> = map.into_iter().filter(|cube| predicate(*cube)).collect();
+    let region: HashSet<_> = cubes.iter().copied().collect();
+    let mut visited = HashSet::new();
+    let mut labels = HashMap::new();
+    let mut label = 0;
+    for cube in cubes {
+        if visited.contains(&cube) {
+            continue;
+        }
+        let component = fill(&region, cube, &mut visited);
+        labels.extend(component.into_iter().map(|cube| (cube, label)));
+        label += 1;
+    }
+    return labels;
+}
+
+// the number of edges between the region and the rest of the map
+pub fn calculate_region_perimeter<T>(region: &HashSet<Cube<T>>) -> usize
+where
+    T: Number + Hash,
+{
+    return get_boundary_edges(region).len();
+}
+
+// the average of all cubes, which can be passed to round_cube
+pub fn calculate_region_centroid<I, F>(region: &HashSet<Cube<I>>) -> Option<Cube<F>>
+where
+    I: AsPrimitive<F>,
+    F: 'static + num::Float,
+    usize: AsPrimitive<F>,
+{
+    if region.is_empty() {
+        return None;
+    }
+    let zero = F::zero();
+    let sum = region.iter().fold((zero, zero, zero), |sum, cube| {
+        (
+            sum.0 + cube.q.as_(),
+            sum.1 + cube.r.as_(),
+            sum.2 + cube.s.as_(),
+        )
+    });
+    let len: F = region.len().as_();
+    return Some(Cube {
+        q: sum.0 / len,
+        r: sum.1 / len,
+        s: sum.2 / len,
+    });
+}
+
+// the ranges of q, r and s covered by the region
+pub fn get_region_bounds<T>(region: &HashSet<Cube<T>>) -> Option<[RangeInclusive<T>; 3]>
+where
+    T: Number,
+{
+    let first = region.iter().next()?;
+    let mut min = *first;
+    let mut max = *first;
+    for cube in region {
+        min = Cube {
+            q: min.q.min(cube.q),
+            r: min.r.min(cube.r),
+            s: min.s.min(cube.s),
+        };
+        max = Cube {
+            q: max.q.max(cube.q),
+            r: max.r.max(cube.r),
+            s: max.s.max(cube.s),
+        };
+    }
+    return Some([min.q..=max.q, min.r..=max.r, min.s..=max.s]);
+}
+
+// connected areas outside of the region which are completely surrounded by it
+pub fn get_region_holes<T>(region: &HashSet<Cube<T>>) -> Vec<HashSet<Cube<T>>>
+where
+    T: Number + Hash,
+    std::ops::Range<T>: Iterator<Item = T>,
+{
+    let Some([q, r, s]) = get_region_bounds(region) else {
+        return Vec::new();
+    };
+    // anything within the bounds grown by one which reaches the border is outside
+    let one = num::one::<T>();
+    let (min_s, max_s) = (*s.start() - one, *s.end() + one);
+    let mut outside = HashSet::new();
+    for q in (*q.start() - one)..(*q.end() + one + one) {
+        for r in (*r.start() - one)..(*r.end() + one + one) {
+            let cube = Cube { q, r, s: -q - r };
+            if cube.s >= min_s && cube.s <= max_s && !region.contains(&cube) {
+                outside.insert(cube);
+            }
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut holes = Vec::new();
+    let directions = get_cube_direction_vectors();
+    for cube in &outside {
+        if visited.contains(cube) {
+            continue;
+        }
+        let component = fill(&outside, *cube, &mut visited);
+        let is_enclosed = component.iter().all(|cube| {
+            directions.iter().all(|direction| {
+                region.contains(&(*cube + *direction)) || outside.contains(&(*cube + *direction))
+            })
+        });
+        if is_enclosed {
+            holes.push(component);
+        }
+    }
+    return holes;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORIGIN: Cube<i32> = Cube { q: 0, r: 0, s: 0 };
+
+    #[test]
+    fn components() {
+        let far = Cube::new(5, 0);
+        let region: HashSet<_> = get_cubes_within_range(ORIGIN, 1)
+            .chain(get_nth_nearest_cubes(far, 1))
+            .collect();
+        let mut components = get_connected_components(&region);
+        components.sort_by_key(|component| component.len());
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].len(), 6);
+        assert_eq!(components[1].len(), 7);
+
+        let labels = label_connected_components(get_cubes_within_range(ORIGIN, 8), |cube| {
+            region.contains(&cube)
+        });
+        assert_eq!(labels.len(), 13);
+        assert_eq!(labels[&Cube::new(-1, 0)], 0);
+        assert_eq!(labels[&ORIGIN], 0);
+        assert_eq!(labels[&Cube::new(4, 0)], 1);
+    }
+
+    #[test]
+    fn analysis() {
+        let region: HashSet<_> = get_cubes_within_range(Cube::new(2, -1), 1).collect();
+        assert_eq!(calculate_region_perimeter(&region), 18);
+        let centroid: Cube<f32> = calculate_region_centroid(&region).unwrap();
+        assert_eq!(centroid, Cube::new(2.0, -1.0));
+        let bounds = get_region_bounds(&region).unwrap();
+        assert_eq!(bounds, [1..=3, -2..=0, -2..=0]);
+        assert!(get_region_holes(&region).is_empty());
+    }
+
+    #[test]
+    fn holes() {
+        let region: HashSet<_> = get_nth_nearest_cubes(ORIGIN, 1).collect();
+        let holes = get_region_holes(&region);
+        assert_eq!(holes, vec![HashSet::from([ORIGIN])]);
+
+        let mut region: HashSet<_> = get_nth_nearest_cubes(ORIGIN, 2).collect();
+        let holes = get_region_holes(&region);
+        assert_eq!(holes.len(), 1);
+        assert_eq!(holes[0].len(), 7);
+
+        region.remove(&Cube::new(2, 0));
+        assert!(get_region_holes(&region).is_empty());
+        assert_eq!(calculate_region_perimeter(&region), 6 * 2 + 4 * 6 + 10);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{shared::coordinate::*, structs::*};
-use std::ops::Neg;
+use core::ops::Neg;
 
 // https://www.redblobgames.com/grids/hexagons/#rounding
 pub fn round_cube<I, F>(frac: Cube<F>) -> Cube<I>
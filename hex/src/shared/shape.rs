@@ -0,0 +1,111 @@
+use crate::{shared::index::*, shared::neighbor::*, structs::*};
+use num::cast::AsPrimitive;
+
+// https://www.redblobgames.com/grids/hexagons/implementation.html#map-shapes
+// q from 0 to size.0, then r from 0 to size.1
+pub fn get_parallelogram_cubes<T: Number>(
+    src: Cube<T>,
+    size: (T, T),
+) -> impl Iterator<Item = Cube<T>>
+where
+    core::ops::Range<T>: Iterator<Item = T>,
+{
+    (num::zero()..size.0).flat_map(move |q| {
+        (num::zero()..size.1).map(move |r| {
+            let s = -q - r;
+            src + Cube { q, r, s }
+        })
+    })
+}
+
+// n cubes per side, q + r < n
+pub fn get_triangle_cubes<T: Number>(src: Cube<T>, n: T) -> impl Iterator<Item = Cube<T>>
+where
+    core::ops::Range<T>: Iterator<Item = T>,
+{
+    (num::zero()..n).flat_map(move |q| {
+        (num::zero()..n - q).map(move |r| {
+            let s = -q - r;
+            src + Cube { q, r, s }
+        })
+    })
+}
+
+// n cubes per side, q + r >= n - 1, pointing to the opposite side of get_triangle_cubes
+pub fn get_reversed_triangle_cubes<T: Number>(src: Cube<T>, n: T) -> impl Iterator<Item = Cube<T>>
+where
+    core::ops::Range<T>: Iterator<Item = T>,
+{
+    let one = num::one();
+    (num::zero()..n).flat_map(move |q| {
+        (n - one - q..n).map(move |r| {
+            let s = -q - r;
+            src + Cube { q, r, s }
+        })
+    })
+}
+
+// same cubes as get_cubes_within_range, in spiral order
+pub fn get_hexagon_cubes<T>(src: Cube<T>, n: usize) -> impl Iterator<Item = Cube<T>>
+where
+    T: Number + 'static,
+    usize: AsPrimitive<T>,
+{
+    (0..calculate_hexagon_size(n)).map(move |index| convert_spiral_index_to_cube(src, index))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use std::collections::HashSet;
+
+    const SRC: Cube<i32> = Cube { q: 1, r: 2, s: -3 };
+
+    #[test]
+    fn parallelogram() {
+        let result: Vec<_> = get_parallelogram_cubes(SRC, (3, 2)).collect();
+        assert_eq!(result.len(), 6);
+        assert_eq!(result[0], SRC);
+        assert_eq!(result[1], SRC + Cube { q: 0, r: 1, s: -1 });
+        assert_eq!(result[2], SRC + Cube { q: 1, r: 0, s: -1 });
+        assert_eq!(result[5], SRC + Cube { q: 2, r: 1, s: -3 });
+    }
+
+    #[test]
+    fn triangle() {
+        const N: i32 = 4;
+        let result: Vec<_> = get_triangle_cubes(SRC, N).collect();
+        assert_eq!(result.len(), 10);
+        assert_eq!(result[0], SRC);
+        let corners = [
+            SRC,
+            SRC + Cube { q: 3, r: 0, s: -3 },
+            SRC + Cube { q: 0, r: 3, s: -3 },
+        ];
+        for corner in corners {
+            assert!(result.contains(&corner), "{:?}", corner);
+        }
+
+        let result: Vec<_> = get_reversed_triangle_cubes(SRC, N).collect();
+        assert_eq!(result.len(), 10);
+        assert_eq!(result[0], SRC + Cube { q: 0, r: 3, s: -3 });
+        let corners = [
+            SRC + Cube { q: 3, r: 3, s: -6 },
+            SRC + Cube { q: 3, r: 0, s: -3 },
+            SRC + Cube { q: 0, r: 3, s: -3 },
+        ];
+        for corner in corners {
+            assert!(result.contains(&corner), "{:?}", corner);
+        }
+    }
+
+    #[test]
+    fn hexagon() {
+        let result: Vec<_> = get_hexagon_cubes(SRC, 3).collect();
+        assert_eq!(result[0], SRC);
+        let result: HashSet<_> = result.into_iter().collect();
+        let exp: HashSet<_> = get_cubes_within_range(SRC, 3).collect();
+        assert_eq!(result, exp);
+    }
+}
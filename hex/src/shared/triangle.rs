@@ -0,0 +1,136 @@
+// every cube is split into 6 triangles, triangle i touches the neighbor in direction i
+// the triangles form a triangular grid whose vertices are the cube centers and corners
+
+use crate::{shared::neighbor::*, structs::*};
+
+fn get_three<T: Number>() -> T {
+    return num::one::<T>() + num::one() + num::one();
+}
+
+impl<T: Number> HexTriangle<T> {
+    // 3 * cube + direction i, the centroid in thirds of a cube
+    pub fn centroid(&self) -> Cube<T> {
+        return self.centroid;
+    }
+}
+
+pub fn convert_cube_to_triangle<T: Number>(cube: Cube<T>, index: usize) -> HexTriangle<T> {
+    let three = get_three();
+    let scaled = Cube {
+        q: cube.q * three,
+        r: cube.r * three,
+        s: cube.s * three,
+    };
+    let centroid = scaled + get_cube_direction_vectors()[index % 6];
+    return HexTriangle { centroid };
+}
+
+pub fn convert_triangle_to_cube<T>(triangle: HexTriangle<T>) -> (Cube<T>, usize)
+where
+    T: Number + num::Integer,
+{
+    let three = get_three::<T>();
+    let one = num::one::<T>();
+    // every component of a direction is -1, 0 or 1, so it is the remainder around 0
+    let remainder = |value: T| (value + one).mod_floor(&three) - one;
+    let centroid = triangle.centroid;
+    let direction = Cube {
+        q: remainder(centroid.q),
+        r: remainder(centroid.r),
+        s: remainder(centroid.s),
+    };
+    let index = get_cube_direction_vectors()
+        .iter()
+        .position(|other| *other == direction)
+        .unwrap();
+    let scaled = centroid - direction;
+    let cube = Cube {
+        q: scaled.q / three,
+        r: scaled.r / three,
+        s: scaled.s / three,
+    };
+    return (cube, index);
+}
+
+pub fn get_cube_triangles<T: Number>(cube: Cube<T>) -> [HexTriangle<T>; 6] {
+    return core::array::from_fn(|i| convert_cube_to_triangle(cube, i));
+}
+
+// the two other triangles of the same cube, then the one across the edge
+pub fn get_triangle_neighbors<T>(triangle: HexTriangle<T>) -> [HexTriangle<T>; 3]
+where
+    T: Number + num::Integer,
+{
+    let (cube, index) = convert_triangle_to_cube(triangle);
+    let neighbor = cube + get_cube_direction_vectors()[index];
+    return [
+        convert_cube_to_triangle(cube, index + 5),
+        convert_cube_to_triangle(cube, index + 1),
+        convert_cube_to_triangle(neighbor, index + 3),
+    ];
+}
+
+// the cube center and the two corners of edge i
+pub fn get_triangle_vertices<T>(triangle: HexTriangle<T>) -> (Cube<T>, [HexVertex<T>; 2])
+where
+    T: Number + num::Integer,
+{
+    let (cube, index) = convert_triangle_to_cube(triangle);
+    let corners = [HexVertex::new(cube, index + 5), HexVertex::new(cube, index)];
+    return (cube, corners);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::edge::*;
+    use std::collections::HashSet;
+
+    const ORIGIN: Cube<i32> = Cube { q: 0, r: 0, s: 0 };
+
+    #[test]
+    fn round_trip() {
+        let mut triangles = HashSet::new();
+        for cube in get_cubes_within_range(ORIGIN, 3) {
+            for (i, triangle) in get_cube_triangles(cube).into_iter().enumerate() {
+                assert_eq!(convert_triangle_to_cube(triangle), (cube, i));
+                assert!(triangle.centroid().is_valid());
+                triangles.insert(triangle);
+            }
+        }
+        assert_eq!(triangles.len(), 6 * 37);
+    }
+
+    #[test]
+    fn neighbors() {
+        for cube in get_cubes_within_range(Cube::new(-4, 1), 1) {
+            for triangle in get_cube_triangles(cube) {
+                let neighbors = get_triangle_neighbors(triangle);
+                for neighbor in neighbors {
+                    assert_ne!(neighbor, triangle);
+                    assert!(get_triangle_neighbors(neighbor).contains(&triangle));
+                    // neighbors share two vertices
+                    let (center, corners) = get_triangle_vertices(triangle);
+                    let (other_center, other_corners) = get_triangle_vertices(neighbor);
+                    let shared = corners
+                        .iter()
+                        .filter(|corner| other_corners.contains(corner))
+                        .count();
+                    let shared = shared + (center == other_center) as usize;
+                    assert_eq!(shared, 2, "{:?} {:?}", triangle, neighbor);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn vertices() {
+        let triangle = convert_cube_to_triangle(ORIGIN, 2);
+        let (center, corners) = get_triangle_vertices(triangle);
+        assert_eq!(center, ORIGIN);
+        let edge = HexEdge::new(ORIGIN, 2);
+        for corner in get_edge_vertices(edge) {
+            assert!(corners.contains(&corner));
+        }
+    }
+}
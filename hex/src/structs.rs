@@ -5,14 +5,41 @@ pub struct Cube<T> {
     pub s: T,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Axial<T> {
     pub q: T,
     pub r: T,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Offset<T> {
     pub q: T,
     pub r: T,
 }
+
+// an edge shared by two cubes, only directions 0, 1 and 2 are used so each edge has one form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexEdge<T> {
+    pub(crate) cube: Cube<T>,
+    pub(crate) direction: usize,
+}
+
+// a vertex shared by three cubes, only corners 0 and 1 are used so each vertex has one form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexVertex<T> {
+    pub(crate) cube: Cube<T>,
+    pub(crate) corner: usize,
+}
+
+// a sixth of a cube, between its center and edge i
+// stored as 3 * cube + direction i, which is the centroid in thirds of a cube, so each triangle has one form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexTriangle<T> {
+    pub(crate) centroid: Cube<T>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Flat,
+    Pointy,
+}
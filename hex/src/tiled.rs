@@ -0,0 +1,643 @@
+// Tiled hexagonal maps, https://doc.mapeditor.org/en/stable/reference/tmx-map-format/
+// Tiled stores rows top to bottom and shifts odd or even columns/rows by half a tile,
+// while flat and pointy have y inversed and a fixed parity
+// tiles are therefore mapped with rows flipped, plus one extra column/row when the parity differs
+
+use crate::{flat, pointy, structs::*};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaggerAxis {
+    // columns are shifted, flat topped
+    X,
+    // rows are shifted, pointy topped
+    Y,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaggerIndex {
+    Odd,
+    Even,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledTileset {
+    pub first_gid: u32,
+    pub source: String,
+}
+
+// data is in Tiled order, row by row from the top, 0 means empty
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledLayer {
+    pub name: String,
+    pub data: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledMap {
+    pub width: usize,
+    pub height: usize,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub hex_side_length: u32,
+    pub stagger_axis: StaggerAxis,
+    pub stagger_index: StaggerIndex,
+    pub tilesets: Vec<TiledTileset>,
+    pub layers: Vec<TiledLayer>,
+}
+
+#[derive(Debug)]
+pub enum TiledError {
+    Xml(roxmltree::Error),
+    Json(serde_json::Error),
+    Missing(&'static str),
+    Invalid(&'static str),
+    UnsupportedEncoding(String),
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TiledError::Xml(error) => write!(f, "invalid xml: {}", error),
+            TiledError::Json(error) => write!(f, "invalid json: {}", error),
+            TiledError::Missing(name) => write!(f, "missing {}", name),
+            TiledError::Invalid(name) => write!(f, "invalid {}", name),
+            TiledError::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported layer encoding {}", encoding)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TiledError {}
+
+impl From<roxmltree::Error> for TiledError {
+    fn from(error: roxmltree::Error) -> Self {
+        TiledError::Xml(error)
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(error: serde_json::Error) -> Self {
+        TiledError::Json(error)
+    }
+}
+
+impl TiledMap {
+    pub fn get_orientation(&self) -> Orientation {
+        return match self.stagger_axis {
+            StaggerAxis::X => Orientation::Flat,
+            StaggerAxis::Y => Orientation::Pointy,
+        };
+    }
+
+    // the size to pass to convert_offset_to_point and friends
+    pub fn get_size(&self) -> (f32, f32) {
+        let width = self.tile_width as f32;
+        let height = self.tile_height as f32;
+        let side = self.hex_side_length as f32;
+        return match self.stagger_axis {
+            StaggerAxis::X => ((width + side) / 2.0, height),
+            StaggerAxis::Y => (width, (height + side) / 2.0),
+        };
+    }
+
+    // flat shifts odd columns up, so the extra column is needed when Tiled shifts odd columns down
+    // pointy shifts odd rows right, which flipped rows turn into odd or even depending on height
+    fn get_shift(&self) -> usize {
+        return match (self.stagger_axis, self.stagger_index) {
+            (StaggerAxis::X, StaggerIndex::Even) => 0,
+            (StaggerAxis::X, StaggerIndex::Odd) => 1,
+            (StaggerAxis::Y, StaggerIndex::Even) => self.height % 2,
+            (StaggerAxis::Y, StaggerIndex::Odd) => (self.height + 1) % 2,
+        };
+    }
+
+    // None if the tile is outside of the map
+    pub fn convert_tile_to_offset(&self, tile: (usize, usize)) -> Option<Offset<i32>> {
+        let (column, row) = tile;
+        if column >= self.width || row >= self.height {
+            return None;
+        }
+        let shift = self.get_shift();
+        let row = self.height - 1 - row;
+        let (q, r) = match self.stagger_axis {
+            StaggerAxis::X => (column + shift, row),
+            StaggerAxis::Y => (column, row + shift),
+        };
+        return Some(Offset {
+            q: i32::try_from(q).ok()?,
+            r: i32::try_from(r).ok()?,
+        });
+    }
+
+    pub fn convert_offset_to_tile(&self, offset: Offset<i32>) -> Option<(usize, usize)> {
+        let shift = self.get_shift() as i32;
+        let (column, row) = match self.stagger_axis {
+            StaggerAxis::X => (offset.q - shift, offset.r),
+            StaggerAxis::Y => (offset.q, offset.r - shift),
+        };
+        if column < 0 || row < 0 || column as usize >= self.width || row as usize >= self.height {
+            return None;
+        }
+        return Some((column as usize, self.height - 1 - row as usize));
+    }
+
+    // None if the layer doesn't exist, data past the map size is skipped
+    pub fn get_tiles(&self, layer: usize) -> Option<impl Iterator<Item = (Offset<i32>, u32)> + '_> {
+        let layer = self.layers.get(layer)?;
+        let tiles = layer
+            .data
+            .iter()
+            .take(self.width.saturating_mul(self.height))
+            .enumerate()
+            .filter_map(move |(index, gid)| {
+                let tile = (index % self.width, index / self.width);
+                return self
+                    .convert_tile_to_offset(tile)
+                    .map(|offset| (offset, *gid));
+            });
+        return Some(tiles);
+    }
+
+    // returns the previous gid, None if the layer or the offset is outside of the map
+    pub fn set_tile(&mut self, layer: usize, offset: Offset<i32>, gid: u32) -> Option<u32> {
+        let (column, row) = self.convert_offset_to_tile(offset)?;
+        let index = row * self.width + column;
+        let tile = self.layers.get_mut(layer)?.data.get_mut(index)?;
+        return Some(std::mem::replace(tile, gid));
+    }
+
+    pub fn get_tile_center(&self, offset: Offset<i32>) -> (f32, f32) {
+        let size = self.get_size();
+        return match self.stagger_axis {
+            StaggerAxis::X => flat::coordinate::convert_offset_to_point(size, offset),
+            StaggerAxis::Y => pointy::coordinate::convert_offset_to_point(size, offset),
+        };
+    }
+}
+
+fn parse_attribute<T: std::str::FromStr>(
+    node: roxmltree::Node,
+    name: &'static str,
+) -> Result<T, TiledError> {
+    let value = node.attribute(name).ok_or(TiledError::Missing(name))?;
+    return value.parse().map_err(|_| TiledError::Invalid(name));
+}
+
+fn parse_stagger(axis: &str, index: &str) -> Result<(StaggerAxis, StaggerIndex), TiledError> {
+    let axis = match axis {
+        "x" => StaggerAxis::X,
+        "y" => StaggerAxis::Y,
+        _ => return Err(TiledError::Invalid("staggeraxis")),
+    };
+    let index = match index {
+        "odd" => StaggerIndex::Odd,
+        "even" => StaggerIndex::Even,
+        _ => return Err(TiledError::Invalid("staggerindex")),
+    };
+    return Ok((axis, index));
+}
+
+fn format_stagger(map: &TiledMap) -> (&'static str, &'static str) {
+    let axis = match map.stagger_axis {
+        StaggerAxis::X => "x",
+        StaggerAxis::Y => "y",
+    };
+    let index = match map.stagger_index {
+        StaggerIndex::Odd => "odd",
+        StaggerIndex::Even => "even",
+    };
+    return (axis, index);
+}
+
+// every tile needs to fit in an Offset<i32>, with one extra column/row for the shift
+fn check_size(map: &TiledMap) -> Result<(), TiledError> {
+    let limit = i32::MAX as usize;
+    if map.width >= limit || map.height >= limit {
+        return Err(TiledError::Invalid("size"));
+    }
+    map.width
+        .checked_mul(map.height)
+        .ok_or(TiledError::Invalid("size"))?;
+    return Ok(());
+}
+
+fn check_layer(map: &TiledMap, layer: &TiledLayer) -> Result<(), TiledError> {
+    if layer.data.len() != map.width * map.height {
+        return Err(TiledError::Invalid("layer data"));
+    }
+    return Ok(());
+}
+
+// only csv encoded layers and external tilesets are supported
+pub fn read_tmx(text: &str) -> Result<TiledMap, TiledError> {
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    if root.attribute("orientation") != Some("hexagonal") {
+        return Err(TiledError::Invalid("orientation"));
+    }
+    let (stagger_axis, stagger_index) = parse_stagger(
+        parse_attribute::<String>(root, "staggeraxis")?.as_str(),
+        parse_attribute::<String>(root, "staggerindex")?.as_str(),
+    )?;
+    let mut map = TiledMap {
+        width: parse_attribute(root, "width")?,
+        height: parse_attribute(root, "height")?,
+        tile_width: parse_attribute(root, "tilewidth")?,
+        tile_height: parse_attribute(root, "tileheight")?,
+        hex_side_length: parse_attribute(root, "hexsidelength")?,
+        stagger_axis,
+        stagger_index,
+        tilesets: Vec::new(),
+        layers: Vec::new(),
+    };
+    check_size(&map)?;
+
+    for node in root.children().filter(|node| node.is_element()) {
+        match node.tag_name().name() {
+            "tileset" => map.tilesets.push(TiledTileset {
+                first_gid: parse_attribute(node, "firstgid")?,
+                source: parse_attribute(node, "source")?,
+            }),
+            "layer" => {
+                let data = node
+                    .children()
+                    .find(|child| child.has_tag_name("data"))
+                    .ok_or(TiledError::Missing("data"))?;
+                let encoding = data.attribute("encoding").unwrap_or("xml");
+                if encoding != "csv" {
+                    return Err(TiledError::UnsupportedEncoding(encoding.to_string()));
+                }
+                let data = data
+                    .text()
+                    .unwrap_or("")
+                    .split(',')
+                    .map(|gid| gid.trim().parse().map_err(|_| TiledError::Invalid("data")))
+                    .collect::<Result<_, _>>()?;
+                let layer = TiledLayer {
+                    name: node.attribute("name").unwrap_or("").to_string(),
+                    data,
+                };
+                check_layer(&map, &layer)?;
+                map.layers.push(layer);
+            }
+            _ => {}
+        }
+    }
+    return Ok(map);
+}
+
+fn escape(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+}
+
+pub fn write_tmx(map: &TiledMap) -> String {
+    let (axis, index) = format_stagger(map);
+    let mut text = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    text += &format!(
+        "<map version=\"1.10\" orientation=\"hexagonal\" renderorder=\"right-down\" \
+         width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" infinite=\"0\" \
+         hexsidelength=\"{}\" staggeraxis=\"{}\" staggerindex=\"{}\">\n",
+        map.width, map.height, map.tile_width, map.tile_height, map.hex_side_length, axis, index
+    );
+    for tileset in &map.tilesets {
+        text += &format!(
+            " <tileset firstgid=\"{}\" source=\"{}\"/>\n",
+            tileset.first_gid,
+            escape(&tileset.source)
+        );
+    }
+    for (id, layer) in map.layers.iter().enumerate() {
+        text += &format!(
+            " <layer id=\"{}\" name=\"{}\" width=\"{}\" height=\"{}\">\n  <data encoding=\"csv\">\n",
+            id + 1,
+            escape(&layer.name),
+            map.width,
+            map.height
+        );
+        let rows: Vec<String> = layer
+            .data
+            .chunks(map.width.max(1))
+            .map(|row| {
+                let row: Vec<String> = row.iter().map(|gid| gid.to_string()).collect();
+                row.join(",")
+            })
+            .collect();
+        text += &rows.join(",\n");
+        text += "\n</data>\n </layer>\n";
+    }
+    text += "</map>\n";
+    return text;
+}
+
+fn get_json<'a>(
+    value: &'a serde_json::Value,
+    name: &'static str,
+) -> Result<&'a serde_json::Value, TiledError> {
+    return value.get(name).ok_or(TiledError::Missing(name));
+}
+
+// numbers that don't fit T are invalid instead of truncated, like the tmx attributes
+fn get_json_number<T: TryFrom<u64>>(
+    value: &serde_json::Value,
+    name: &'static str,
+) -> Result<T, TiledError> {
+    return get_json(value, name)?
+        .as_u64()
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or(TiledError::Invalid(name));
+}
+
+fn get_json_str<'a>(
+    value: &'a serde_json::Value,
+    name: &'static str,
+) -> Result<&'a str, TiledError> {
+    return get_json(value, name)?
+        .as_str()
+        .ok_or(TiledError::Invalid(name));
+}
+
+// only array encoded layers and external tilesets are supported
+pub fn read_tmj(text: &str) -> Result<TiledMap, TiledError> {
+    let root: serde_json::Value = serde_json::from_str(text)?;
+    if get_json_str(&root, "orientation")? != "hexagonal" {
+        return Err(TiledError::Invalid("orientation"));
+    }
+    let (stagger_axis, stagger_index) = parse_stagger(
+        get_json_str(&root, "staggeraxis")?,
+        get_json_str(&root, "staggerindex")?,
+    )?;
+    let mut map = TiledMap {
+        width: get_json_number(&root, "width")?,
+        height: get_json_number(&root, "height")?,
+        tile_width: get_json_number(&root, "tilewidth")?,
+        tile_height: get_json_number(&root, "tileheight")?,
+        hex_side_length: get_json_number(&root, "hexsidelength")?,
+        stagger_axis,
+        stagger_index,
+        tilesets: Vec::new(),
+        layers: Vec::new(),
+    };
+    check_size(&map)?;
+
+    let tilesets = get_json(&root, "tilesets")?
+        .as_array()
+        .ok_or(TiledError::Invalid("tilesets"))?;
+    for tileset in tilesets {
+        map.tilesets.push(TiledTileset {
+            first_gid: get_json_number(tileset, "firstgid")?,
+            source: get_json_str(tileset, "source")?.to_string(),
+        });
+    }
+
+    let layers = get_json(&root, "layers")?
+        .as_array()
+        .ok_or(TiledError::Invalid("layers"))?;
+    for layer in layers {
+        if get_json_str(layer, "type")? != "tilelayer" {
+            continue;
+        }
+        if let Some(encoding) = layer.get("encoding").and_then(|encoding| encoding.as_str()) {
+            if encoding != "csv" {
+                return Err(TiledError::UnsupportedEncoding(encoding.to_string()));
+            }
+        }
+        let data = get_json(layer, "data")?
+            .as_array()
+            .ok_or(TiledError::Invalid("data"))?
+            .iter()
+            .map(|gid| gid.as_u64().and_then(|gid| u32::try_from(gid).ok()))
+            .collect::<Option<_>>()
+            .ok_or(TiledError::Invalid("data"))?;
+        let layer = TiledLayer {
+            name: layer
+                .get("name")
+                .and_then(|name| name.as_str())
+                .unwrap_or("")
+                .to_string(),
+            data,
+        };
+        check_layer(&map, &layer)?;
+        map.layers.push(layer);
+    }
+    return Ok(map);
+}
+
+pub fn write_tmj(map: &TiledMap) -> String {
+    let (axis, index) = format_stagger(map);
+    let tilesets: Vec<_> = map
+        .tilesets
+        .iter()
+        .map(|tileset| {
+            serde_json::json!({
+                "firstgid": tileset.first_gid,
+                "source": tileset.source,
+            })
+        })
+        .collect();
+    let layers: Vec<_> = map
+        .layers
+        .iter()
+        .enumerate()
+        .map(|(id, layer)| {
+            serde_json::json!({
+                "id": id + 1,
+                "type": "tilelayer",
+                "name": layer.name,
+                "x": 0,
+                "y": 0,
+                "width": map.width,
+                "height": map.height,
+                "opacity": 1,
+                "visible": true,
+                "data": layer.data,
+            })
+        })
+        .collect();
+    let root = serde_json::json!({
+        "type": "map",
+        "version": "1.10",
+        "orientation": "hexagonal",
+        "renderorder": "right-down",
+        "infinite": false,
+        "width": map.width,
+        "height": map.height,
+        "tilewidth": map.tile_width,
+        "tileheight": map.tile_height,
+        "hexsidelength": map.hex_side_length,
+        "staggeraxis": axis,
+        "staggerindex": index,
+        "nextlayerid": map.layers.len() + 1,
+        "nextobjectid": 1,
+        "tilesets": tilesets,
+        "layers": layers,
+    });
+    return serde_json::to_string_pretty(&root).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" tiledversion="1.10.2" orientation="hexagonal" renderorder="right-down" width="3" height="2" tilewidth="32" tileheight="28" infinite="0" hexsidelength="16" staggeraxis="x" staggerindex="odd" nextlayerid="2" nextobjectid="1">
+ <tileset firstgid="1" source="terrain.tsx"/>
+ <layer id="1" name="ground" width="3" height="2">
+  <data encoding="csv">
+1,2,3,
+4,5,6
+</data>
+ </layer>
+</map>
+"#;
+
+    fn new_map(axis: StaggerAxis, index: StaggerIndex, width: usize, height: usize) -> TiledMap {
+        return TiledMap {
+            width,
+            height,
+            tile_width: 32,
+            tile_height: 28,
+            hex_side_length: 16,
+            stagger_axis: axis,
+            stagger_index: index,
+            tilesets: vec![],
+            layers: vec![TiledLayer {
+                name: String::from("ground"),
+                data: (0..(width * height) as u32).collect(),
+            }],
+        };
+    }
+
+    // tile centers as Tiled renders them, with y pointing down
+    fn get_tiled_center(map: &TiledMap, tile: (usize, usize)) -> (f32, f32) {
+        let (column, row) = (tile.0 as f32, tile.1 as f32);
+        let size = map.get_size();
+        let shifted = match (map.stagger_axis, map.stagger_index) {
+            (StaggerAxis::X, StaggerIndex::Odd) => tile.0 % 2 == 1,
+            (StaggerAxis::X, StaggerIndex::Even) => tile.0 % 2 != 1,
+            (StaggerAxis::Y, StaggerIndex::Odd) => tile.1 % 2 == 1,
+            (StaggerAxis::Y, StaggerIndex::Even) => tile.1 % 2 != 1,
+        };
+        let shift = if shifted { 0.5 } else { 0.0 };
+        return match map.stagger_axis {
+            StaggerAxis::X => (column * size.0, (row + shift) * size.1),
+            StaggerAxis::Y => ((column + shift) * size.0, row * size.1),
+        };
+    }
+
+    #[test]
+    fn geometry() {
+        let axes = [StaggerAxis::X, StaggerAxis::Y];
+        let indexes = [StaggerIndex::Odd, StaggerIndex::Even];
+        for axis in axes {
+            for index in indexes {
+                for height in [3, 4] {
+                    let map = new_map(axis, index, 4, height);
+                    let origin = map.get_tile_center(map.convert_tile_to_offset((0, 0)).unwrap());
+                    for column in 0..map.width {
+                        for row in 0..map.height {
+                            let offset = map.convert_tile_to_offset((column, row)).unwrap();
+                            assert_eq!(map.convert_offset_to_tile(offset), Some((column, row)));
+
+                            let tiled = get_tiled_center(&map, (column, row));
+                            let center = map.get_tile_center(offset);
+                            let center = (center.0 - origin.0, origin.1 - center.1);
+                            let exp = get_tiled_center(&map, (0, 0));
+                            let exp = (tiled.0 - exp.0, tiled.1 - exp.1);
+                            assert_eq!(center, exp, "{:?} {:?} {}", axis, index, height);
+                        }
+                    }
+                    assert_eq!(map.convert_tile_to_offset((map.width, 0)), None);
+                    assert_eq!(map.convert_tile_to_offset((0, map.height)), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tmx() {
+        let map = read_tmx(TMX).unwrap();
+        assert_eq!(map.get_orientation(), Orientation::Flat);
+        assert_eq!(map.stagger_index, StaggerIndex::Odd);
+        assert_eq!(map.tilesets[0].source, "terrain.tsx");
+        assert_eq!(map.layers[0].data, vec![1, 2, 3, 4, 5, 6]);
+
+        let tiles: Vec<_> = map.get_tiles(0).unwrap().collect();
+        assert_eq!(tiles[0], (Offset { q: 1, r: 1 }, 1));
+        assert_eq!(tiles[5], (Offset { q: 3, r: 0 }, 6));
+
+        assert_eq!(read_tmx(&write_tmx(&map)).unwrap(), map);
+    }
+
+    #[test]
+    fn tmj() {
+        let mut map = new_map(StaggerAxis::Y, StaggerIndex::Even, 3, 3);
+        map.tilesets.push(TiledTileset {
+            first_gid: 1,
+            source: String::from("terrain.tsj"),
+        });
+        assert_eq!(map.set_tile(0, Offset { q: 0, r: 1 }, 42), Some(6));
+        assert_eq!(map.set_tile(0, Offset { q: 0, r: 4 }, 42), None);
+        assert_eq!(map.set_tile(1, Offset { q: 0, r: 1 }, 42), None);
+        assert_eq!(map.layers[0].data[6], 42);
+        assert!(map.get_tiles(1).is_none());
+        assert_eq!(read_tmj(&write_tmj(&map)).unwrap(), map);
+    }
+
+    #[test]
+    fn unsupported() {
+        let text = TMX.replace("encoding=\"csv\"", "encoding=\"base64\"");
+        assert!(matches!(
+            read_tmx(&text),
+            Err(TiledError::UnsupportedEncoding(_))
+        ));
+        let text = TMX.replace("1,2,3,", "1,2,");
+        assert!(matches!(
+            read_tmx(&text),
+            Err(TiledError::Invalid("layer data"))
+        ));
+    }
+
+    // both formats reject the same maps
+    #[test]
+    fn invalid() {
+        let map = new_map(StaggerAxis::X, StaggerIndex::Odd, 3, 2);
+        let tmj = write_tmj(&map);
+
+        let text = TMX.replace(" staggeraxis=\"x\"", "");
+        assert!(matches!(
+            read_tmx(&text),
+            Err(TiledError::Missing("staggeraxis"))
+        ));
+        let text = tmj.replace("\"staggeraxis\"", "\"axis\"");
+        assert!(matches!(
+            read_tmj(&text),
+            Err(TiledError::Missing("staggeraxis"))
+        ));
+
+        let text = TMX.replace("tilewidth=\"32\"", "tilewidth=\"4294967328\"");
+        assert!(matches!(
+            read_tmx(&text),
+            Err(TiledError::Invalid("tilewidth"))
+        ));
+        let text = tmj.replace("\"tilewidth\": 32", "\"tilewidth\": 4294967328");
+        assert!(matches!(
+            read_tmj(&text),
+            Err(TiledError::Invalid("tilewidth"))
+        ));
+
+        let text = tmj.replace(
+            "\"data\": [\n        0,",
+            "\"data\": [\n        4294967296,",
+        );
+        assert!(matches!(read_tmj(&text), Err(TiledError::Invalid("data"))));
+
+        let text = TMX.replace("height=\"2\" tilewidth", "height=\"4294967296\" tilewidth");
+        assert!(matches!(read_tmx(&text), Err(TiledError::Invalid("size"))));
+    }
+}
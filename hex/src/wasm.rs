@@ -0,0 +1,306 @@
+// wasm-bindgen bindings for javascript, coordinates are i32 and points are f64
+// the exports end up in any cdylib depending on this crate with the wasm feature, like hex-wasm
+// test with `wasm-pack test --node hex --features wasm`
+// overflows throw an Error in javascript, like the OverflowError of the python bindings
+
+use crate::{
+    error::*, shared::coordinate, shared::index, shared::line, shared::neighbor, structs::*,
+};
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(js_name = Orientation)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsOrientation {
+    Flat = 0,
+    Pointy = 1,
+}
+
+impl From<JsOrientation> for Orientation {
+    fn from(orientation: JsOrientation) -> Self {
+        return match orientation {
+            JsOrientation::Flat => Orientation::Flat,
+            JsOrientation::Pointy => Orientation::Pointy,
+        };
+    }
+}
+
+#[wasm_bindgen(js_name = Cube)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsCube {
+    q: i32,
+    r: i32,
+    s: i32,
+}
+
+// the fields are read only and s is derived from q and r, so every cube made from javascript is valid
+#[wasm_bindgen(js_class = Cube)]
+impl JsCube {
+    #[wasm_bindgen(constructor)]
+    pub fn new(q: i32, r: i32) -> Result<JsCube, JsError> {
+        let cube = coordinate::try_convert_axial_to_cube(Axial { q, r })?;
+        return Ok(cube.into());
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn q(&self) -> i32 {
+        return self.q;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn r(&self) -> i32 {
+        return self.r;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn s(&self) -> i32 {
+        return self.s;
+    }
+}
+
+#[wasm_bindgen(js_name = Axial)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsAxial {
+    pub q: i32,
+    pub r: i32,
+}
+
+#[wasm_bindgen(js_class = Axial)]
+impl JsAxial {
+    #[wasm_bindgen(constructor)]
+    pub fn new(q: i32, r: i32) -> JsAxial {
+        return JsAxial { q, r };
+    }
+}
+
+#[wasm_bindgen(js_name = Offset)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsOffset {
+    pub q: i32,
+    pub r: i32,
+}
+
+#[wasm_bindgen(js_class = Offset)]
+impl JsOffset {
+    #[wasm_bindgen(constructor)]
+    pub fn new(q: i32, r: i32) -> JsOffset {
+        return JsOffset { q, r };
+    }
+}
+
+#[wasm_bindgen(js_name = Point)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JsPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[wasm_bindgen(js_class = Point)]
+impl JsPoint {
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: f64, y: f64) -> JsPoint {
+        return JsPoint { x, y };
+    }
+}
+
+impl From<JsCube> for Cube<i32> {
+    fn from(cube: JsCube) -> Self {
+        return Cube {
+            q: cube.q,
+            r: cube.r,
+            s: cube.s,
+        };
+    }
+}
+
+impl From<Cube<i32>> for JsCube {
+    fn from(cube: Cube<i32>) -> Self {
+        return JsCube {
+            q: cube.q,
+            r: cube.r,
+            s: cube.s,
+        };
+    }
+}
+
+impl From<JsOffset> for Offset<i32> {
+    fn from(offset: JsOffset) -> Self {
+        return Offset {
+            q: offset.q,
+            r: offset.r,
+        };
+    }
+}
+
+impl From<Offset<i32>> for JsOffset {
+    fn from(offset: Offset<i32>) -> Self {
+        return JsOffset {
+            q: offset.q,
+            r: offset.r,
+        };
+    }
+}
+
+#[wasm_bindgen(js_name = convertPointToOffset)]
+pub fn convert_point_to_offset(
+    orientation: JsOrientation,
+    size: JsPoint,
+    point: JsPoint,
+) -> Result<JsOffset, JsError> {
+    let orientation = Orientation::from(orientation);
+    let offset = orientation
+        .try_convert_point_to_offset::<i32, f64>((size.x, size.y), (point.x, point.y))?;
+    return Ok(offset.into());
+}
+
+#[wasm_bindgen(js_name = convertOffsetToPoint)]
+pub fn convert_offset_to_point(
+    orientation: JsOrientation,
+    size: JsPoint,
+    offset: JsOffset,
+) -> Result<JsPoint, JsError> {
+    let orientation = Orientation::from(orientation);
+    let (x, y) = orientation.try_convert_offset_to_point((size.x, size.y), Offset::from(offset))?;
+    return Ok(JsPoint { x, y });
+}
+
+#[wasm_bindgen(js_name = convertOffsetToCube)]
+pub fn convert_offset_to_cube(
+    orientation: JsOrientation,
+    offset: JsOffset,
+) -> Result<JsCube, JsError> {
+    let orientation = Orientation::from(orientation);
+    let cube = orientation.try_convert_offset_to_cube(Offset::from(offset))?;
+    return Ok(cube.into());
+}
+
+#[wasm_bindgen(js_name = convertCubeToOffset)]
+pub fn convert_cube_to_offset(
+    orientation: JsOrientation,
+    cube: JsCube,
+) -> Result<JsOffset, JsError> {
+    let orientation = Orientation::from(orientation);
+    let offset = orientation.try_convert_cube_to_offset(Cube::from(cube))?;
+    return Ok(offset.into());
+}
+
+#[wasm_bindgen(js_name = convertCubeToAxial)]
+pub fn convert_cube_to_axial(cube: JsCube) -> JsAxial {
+    let axial = coordinate::convert_cube_to_axial(Cube::from(cube));
+    return JsAxial {
+        q: axial.q,
+        r: axial.r,
+    };
+}
+
+#[wasm_bindgen(js_name = convertAxialToCube)]
+pub fn convert_axial_to_cube(axial: JsAxial) -> Result<JsCube, JsError> {
+    return JsCube::new(axial.q, axial.r);
+}
+
+#[wasm_bindgen(js_name = calculateDistance)]
+pub fn calculate_distance(src: JsCube, dst: JsCube) -> Result<i32, JsError> {
+    let distance = neighbor::calculate_checked_distance(Cube::from(src), Cube::from(dst));
+    return Ok(distance.ok_or(OverflowError)?);
+}
+
+// the cubes are reserved up front, so a range too large for the wasm memory throws instead of aborting
+#[wasm_bindgen(js_name = getCubesWithinRange)]
+pub fn get_cubes_within_range(src: JsCube, n: i32) -> Result<Vec<JsCube>, JsError> {
+    let cubes = neighbor::try_get_cubes_within_range(Cube::from(src), n)?;
+    let count = usize::try_from(n).map_err(|_| OverflowError)?;
+    let mut out = Vec::new();
+    out.try_reserve_exact(index::try_calculate_hexagon_size(count)?)?;
+    out.extend(cubes.map(JsCube::from));
+    return Ok(out);
+}
+
+#[wasm_bindgen(js_name = getLineCubes)]
+pub fn get_line_cubes(src: JsCube, dst: JsCube) -> Result<Vec<JsCube>, JsError> {
+    let (src, dst) = (Cube::from(src), Cube::from(dst));
+    // the line is interpolated in f64, only the distance has to fit
+    neighbor::calculate_checked_distance(src, dst).ok_or(OverflowError)?;
+    return Ok(line::get_line_cubes::<i32, f64>(src, dst)
+        .into_iter()
+        .map(JsCube::from)
+        .collect());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    // JsError has no Debug, and creating one needs a javascript host, so errors are only tested on wasm32
+    fn ok<T>(result: Result<T, JsError>) -> T {
+        let Ok(value) = result else {
+            panic!("threw an Error");
+        };
+        return value;
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn conversions() {
+        let size = JsPoint::new(42.0, 30.0);
+        for orientation in [JsOrientation::Flat, JsOrientation::Pointy] {
+            for offset in [
+                JsOffset::new(0, 0),
+                JsOffset::new(3, -2),
+                JsOffset::new(-5, 4),
+            ] {
+                let point = ok(convert_offset_to_point(orientation, size, offset));
+                assert_eq!(
+                    ok(convert_point_to_offset(orientation, size, point)),
+                    offset
+                );
+                let cube = ok(convert_offset_to_cube(orientation, offset));
+                assert_eq!(cube.q + cube.r + cube.s, 0);
+                assert_eq!(ok(convert_cube_to_offset(orientation, cube)), offset);
+                let axial = convert_cube_to_axial(cube);
+                assert_eq!(ok(convert_axial_to_cube(axial)), cube);
+            }
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn neighbors() {
+        let src = ok(JsCube::new(0, 0));
+        let dst = ok(JsCube::new(3, -1));
+        assert_eq!(dst.s, -2);
+        assert_eq!(ok(calculate_distance(src, dst)), 3);
+        assert_eq!(ok(get_cubes_within_range(src, 2)).len(), 19);
+        let line = ok(get_line_cubes(src, dst));
+        assert_eq!(line.len(), 4);
+        assert_eq!(line[0], src);
+        assert_eq!(line[3], dst);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn overflow() {
+        let size = JsPoint::new(42.0, 30.0);
+        let far = JsOffset::new(i32::MIN, i32::MIN);
+        let big = ok(JsCube::new(i32::MAX, -i32::MAX));
+        let other = ok(JsCube::new(-1, 0));
+        assert!(JsCube::new(i32::MIN, 0).is_err());
+        assert!(convert_axial_to_cube(JsAxial::new(i32::MIN, 0)).is_err());
+        for orientation in [JsOrientation::Flat, JsOrientation::Pointy] {
+            assert!(convert_offset_to_cube(orientation, far).is_err());
+            assert!(convert_offset_to_point(orientation, size, far).is_err());
+            assert!(
+                convert_point_to_offset(orientation, size, JsPoint::new(f64::NAN, 0.0)).is_err()
+            );
+            assert!(convert_point_to_offset(orientation, size, JsPoint::new(1e300, 0.0)).is_err());
+        }
+        assert!(calculate_distance(big, other).is_err());
+        assert!(get_line_cubes(big, other).is_err());
+        assert!(get_cubes_within_range(big, 1).is_err());
+        assert!(get_cubes_within_range(other, -1).is_err());
+        assert!(get_cubes_within_range(other, i32::MAX).is_err());
+        // fits in i32 but not in the wasm memory
+        assert!(get_cubes_within_range(other, 1 << 20).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// C test harness for the ffi feature, run with tests/ffi/run.sh
+#include <assert.h>
+#include <math.h>
+#include <stdint.h>
+#include <stdio.h>
+#include "hex.h"
+
+static int32_t distance(HexCube a, HexCube b) {
+    int32_t out = -1;
+    assert(hex_calculate_distance(a, b, &out));
+    return out;
+}
+
+static void conversions(void) {
+    HexPoint size = {42.0, 30.0};
+    HexOrientation orientations[] = {HEX_FLAT, HEX_POINTY};
+    for (int i = 0; i < 2; i++) {
+        HexOffset offset = {3, -2};
+        HexCube cube;
+        assert(hex_convert_offset_to_cube(orientations[i], offset, &cube));
+        assert(cube.q + cube.r + cube.s == 0);
+        HexOffset back;
+        assert(hex_convert_cube_to_offset(orientations[i], cube, &back));
+        assert(back.q == offset.q && back.r == offset.r);
+
+        HexAxial axial;
+        assert(hex_convert_cube_to_axial(cube, &axial));
+        HexCube from_axial;
+        assert(hex_convert_axial_to_cube(axial, &from_axial));
+        assert(from_axial.q == cube.q && from_axial.r == cube.r && from_axial.s == cube.s);
+
+        HexPoint point;
+        assert(hex_convert_offset_to_point(orientations[i], size, offset, &point));
+        HexOffset picked;
+        assert(hex_convert_point_to_offset(orientations[i], size, point, &picked));
+        assert(picked.q == offset.q && picked.r == offset.r);
+    }
+}
+
+static void invalid(void) {
+    HexPoint size = {42.0, 30.0};
+    HexCube bad = {1, 1, 5};
+    HexCube edge = {INT32_MAX, -INT32_MAX, 0};
+    HexCube cube = {7, 7, -14};
+    HexOffset offset = {INT32_MIN, INT32_MIN};
+    assert(!hex_convert_offset_to_cube(HEX_FLAT, offset, &cube));
+    assert(cube.q == 7 && cube.r == 7 && cube.s == -14);
+    assert(!hex_convert_cube_to_offset(HEX_POINTY, bad, &offset));
+    assert(!hex_convert_point_to_offset(HEX_FLAT, size, (HexPoint){NAN, 0.0}, &offset));
+    assert(!hex_convert_point_to_offset(HEX_FLAT, size, (HexPoint){1e300, 0.0}, &offset));
+    int32_t out;
+    assert(!hex_calculate_distance(bad, cube, &out));
+    HexCube neighbors[6];
+    assert(!hex_get_neighbors(edge, neighbors));
+    assert(hex_get_cubes_within_range(bad, 2, NULL, 0) == 0);
+    assert(hex_get_cubes_within_range(cube, -1, NULL, 0) == 0);
+    assert(!hex_convert_offset_to_cube(2, (HexOffset){0, 0}, &cube));
+    assert(hex_map_new(2, 10, 8) == NULL);
+    assert(hex_map_new(HEX_FLAT, (size_t)INT32_MAX + 1, 1) == NULL);
+    assert(hex_map_new(HEX_FLAT, INT32_MAX, INT32_MAX) == NULL);
+}
+
+static void neighbors(void) {
+    HexCube cube = {1, -1, 0};
+    HexCube out[19];
+    assert(hex_get_neighbors(cube, out));
+    for (int i = 0; i < 6; i++) {
+        assert(distance(cube, out[i]) == 1);
+    }
+    assert(hex_get_cubes_within_range(cube, 2, NULL, 0) == 19);
+    assert(hex_get_cubes_within_range(cube, 2, out, 19) == 19);
+    for (int i = 0; i < 19; i++) {
+        assert(distance(cube, out[i]) <= 2);
+    }
+}
+
+static void pathfinding(void) {
+    HexMap *map = hex_map_new(HEX_FLAT, 10, 8);
+    assert(map != NULL);
+    for (int r = 1; r < 8; r++) {
+        assert(hex_map_set_cost(map, (HexOffset){5, r}, 0));
+    }
+    assert(!hex_map_set_cost(map, (HexOffset){10, 0}, 1));
+    assert(hex_map_get_cost(map, (HexOffset){5, 1}) == 0);
+
+    HexOffset start = {0, 7};
+    HexOffset goal = {9, 7};
+    uint32_t total_cost = 0;
+    size_t length = hex_map_find_path(map, start, goal, NULL, 0, &total_cost);
+    assert(length > 0 && total_cost == length - 1);
+
+    HexOffset path[64];
+    assert(hex_map_find_path(map, start, goal, path, 64, NULL) == length);
+    assert(path[0].q == start.q && path[0].r == start.r);
+    assert(path[length - 1].q == goal.q && path[length - 1].r == goal.r);
+    for (size_t i = 0; i + 1 < length; i++) {
+        HexCube a, b;
+        assert(hex_convert_offset_to_cube(HEX_FLAT, path[i], &a));
+        assert(hex_convert_offset_to_cube(HEX_FLAT, path[i + 1], &b));
+        assert(distance(a, b) == 1);
+        assert(path[i].q != 5 || path[i].r == 0);
+    }
+
+    hex_map_set_cost(map, (HexOffset){5, 0}, 0);
+    assert(hex_map_find_path(map, start, goal, NULL, 0, NULL) == 0);
+    hex_map_free(map);
+}
+
+int main(void) {
+    conversions();
+    invalid();
+    neighbors();
+    pathfinding();
+    printf("ffi ok\n");
+    return 0;
+}
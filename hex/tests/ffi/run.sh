@@ -0,0 +1,7 @@
+#!/bin/sh
+# builds the static library and runs the C test harness against include/hex.h
+set -e
+cd "$(dirname "$0")/../.."
+cargo rustc --release --features ffi --crate-type staticlib
+cc -std=c99 -Wall -Wextra -Werror -Iinclude tests/ffi/main.c target/release/libhex.a -lpthread -ldl -lm -o target/ffi_test
+./target/ffi_test